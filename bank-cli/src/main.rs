@@ -0,0 +1,117 @@
+//Headless access to the same bank files the GUI works with, for scripts and CI
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use clap::{Parser, Subcommand};
+use bank_model::{BankAccount, BankErrors, BankState, BankUser, Customer, Transaction};
+use bank_model::card::CardNumber;
+use bank_model::journal::JOURNAL_FILE;
+use bank_model::phone::PhoneNumber;
+use bank_model::storage::{BANK_FILE, checkpoint, create_bank, open_bank};
+
+#[derive(Debug, Parser)]
+#[command(name = "bank-cli", about = "Drive the bank without the GUI")]
+struct Cli {
+    /// Snapshot of the bank
+    #[arg(long, default_value = BANK_FILE)]
+    bank: PathBuf,
+    /// Journal of operations made after the snapshot
+    #[arg(long, default_value = JOURNAL_FILE)]
+    journal: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Start a new empty bank, refuses to replace an existing one
+    CreateBank { name: String },
+    /// Register a customer together with their first card, prints the card number
+    CreateUser {
+        fio: String,
+        phone: String,
+        #[arg(default_value = "0")]
+        balance: String,
+    },
+    /// Delete the customer owning the card, with all their cards
+    DeleteUser { card: String },
+    /// Send money from one card to another
+    TransferCard { from: String, to: String, amount: String },
+    /// Send money to the main card of the customer with this phone
+    TransferPhone { from: String, phone: String, amount: String },
+    /// One line per card: customer id, FIO, phone, card number, balance
+    ListUsers,
+    /// Transactions of the whole bank or of one card, oldest first
+    History {
+        #[arg(long)]
+        card: Option<String>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), BankErrors> {
+    if let Command::CreateBank { name } = cli.command {
+        let bank = create_bank(name, &cli.bank, &cli.journal)?;
+        println!("Bank {} created", bank.name);
+        return Ok(())
+    }
+    let mut bank = open_bank(&cli.bank, &cli.journal)?
+        .ok_or_else(|| BankErrors::StorageProblem(format!("No bank at {}, run create-bank first", cli.bank.display())))?;
+
+    match cli.command {
+        Command::CreateBank { .. } => unreachable!("handled above"),
+        Command::CreateUser { fio, phone, balance } => {
+            bank.create_user(fio, phone.clone(), balance)?;
+            let phone: PhoneNumber = phone.parse()?;
+            if let Some(card) = bank.find_card_by_phone(&phone) {
+                println!("{}", card.digits());
+            }
+        }
+        Command::DeleteUser { card } => bank.delete_user(card)?,
+        Command::TransferCard { from, to, amount } => print_transaction(&bank.transfer_by_card(from, to, amount)?),
+        Command::TransferPhone { from, phone, amount } => print_transaction(&bank.transfer_by_phone(from, phone, amount)?),
+        Command::ListUsers => list_users(&bank),
+        Command::History { card } => {
+            let card = card.map(|card| card.trim().parse::<CardNumber>()).transpose()?;
+            bank.get_transactions().iter()
+                .filter(|tx| card.is_none_or(|card| tx.involves(card)))
+                .for_each(print_transaction);
+        }
+    }
+    checkpoint(&mut bank, &cli.bank)
+}
+
+fn list_users(bank: &BankState) {
+    let mut customers: Vec<&Customer> = bank.get_customers().values().collect();
+    customers.sort_by_key(|customer| customer.get_id());
+    for customer in customers {
+        for account in bank.get_customer_accounts(customer) {
+            println!("{}\t{}\t{}\t{}\t{}", customer.get_id(), customer.check_fio(), customer.check_phone(), account.check_card_number().digits(), account.check_balance());
+        }
+    }
+}
+
+//Tab separated: id, time, kind, status, amount, sender card, recipient, credited amount
+fn print_transaction(tx: &Transaction) {
+    println!(
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        tx.get_id(),
+        tx.get_tx_time().to_rfc3339(),
+        tx.get_kind().code(),
+        tx.get_status().code(),
+        tx.get_amount(),
+        tx.get_sender_card().map(|card| card.digits()).unwrap_or_else(|| "-".to_string()),
+        tx.get_recipient(),
+        tx.get_credited_amount()
+    );
+}
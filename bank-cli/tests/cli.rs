@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+struct BankFiles {
+    dir: PathBuf,
+}
+impl BankFiles {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("bank-cli-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self { dir }
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_bank-cli"))
+            .arg("--bank").arg(self.dir.join("bank.json"))
+            .arg("--journal").arg(self.dir.join("bank.journal"))
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    fn ok(&self, args: &[&str]) -> String {
+        let output = self.run(args);
+        assert!(output.status.success(), "{args:?} failed: {}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    }
+}
+impl Drop for BankFiles {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[test]
+fn bank_is_driven_across_runs() {
+    let files = BankFiles::new("flow");
+    files.ok(&["create-bank", "Scripted"]);
+    let first = files.ok(&["create-user", "Ivanov I.I.", "+79000000111", "100"]).trim().to_string();
+    let second = files.ok(&["create-user", "Petrov P.P.", "89000000222"]).trim().to_string();
+    assert_eq!(first.len(), 16);
+
+    files.ok(&["transfer-card", &first, &second, "30"]);
+    files.ok(&["transfer-phone", &second, "+79000000111", "5.50"]);
+
+    let users = files.ok(&["list-users"]);
+    assert_eq!(users.lines().collect::<Vec<_>>(), vec![
+        format!("1\tIvanov I.I.\t+79000000111\t{first}\t75.50 RUB"),
+        format!("2\tPetrov P.P.\t+79000000222\t{second}\t24.50 RUB"),
+    ]);
+
+    let history = files.ok(&["history", "--card", &second]);
+    assert_eq!(history.lines().count(), 2);
+    assert!(history.lines().all(|line| line.contains("\ttransfer\tcompleted\t")));
+
+    files.ok(&["delete-user", &second]);
+    assert_eq!(files.ok(&["list-users"]).lines().count(), 1);
+}
+
+#[test]
+fn errors_go_to_stderr_with_failure_status() {
+    let files = BankFiles::new("errors");
+    let output = files.run(&["list-users"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("run create-bank first"));
+
+    files.ok(&["create-bank", "Scripted"]);
+    let card = files.ok(&["create-user", "Ivanov I.I.", "+79000000111", "10"]).trim().to_string();
+    let output = files.run(&["transfer-phone", &card, "+79000000999", "1"]);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr).trim(), "Recipient +79000000999 was not found");
+}
+
+#[test]
+fn create_bank_keeps_an_existing_bank() {
+    let files = BankFiles::new("recreate");
+    files.ok(&["create-bank", "Scripted"]);
+    let card = files.ok(&["create-user", "Ivanov I.I.", "+79000000111", "10"]).trim().to_string();
+
+    let output = files.run(&["create-bank", "Other"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already holds"));
+
+    assert_eq!(files.ok(&["list-users"]).lines().collect::<Vec<_>>(), vec![
+        format!("1\tIvanov I.I.\t+79000000111\t{card}\t10.00 RUB"),
+    ]);
+}
@@ -0,0 +1,53 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use bank_model::{BankState, BankUser};
+use bank_model::phone::PhoneNumber;
+
+const BANK_SIZES: [u64; 3] = [1_000, 10_000, 100_000];
+
+fn phone_of(i: u64) -> String {
+    format!("+7900{i:07}")
+}
+
+fn bank_with_customers(count: u64) -> BankState {
+    let mut bank = BankState::new("Bench".to_string());
+    for i in 0..count {
+        bank.create_user(format!("Customer{i} Bench B."), phone_of(i), "100".to_string()).unwrap();
+    }
+    bank
+}
+
+fn phone_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("phone lookup");
+    for size in BANK_SIZES {
+        let bank = bank_with_customers(size);
+        let phone: PhoneNumber = phone_of(size / 2).parse().unwrap();
+
+        group.bench_with_input(BenchmarkId::new("index", size), &phone, |b, phone| {
+            b.iter(|| bank.find_card_by_phone(black_box(phone)))
+        });
+        //What every phone transfer did before the index existed
+        group.bench_with_input(BenchmarkId::new("scan", size), &phone, |b, phone| {
+            b.iter(|| bank.get_customers().values()
+                .find(|customer| customer.check_phone() == black_box(phone).as_str())
+                .map(|customer| customer.check_main_card()))
+        });
+    }
+    group.finish();
+}
+
+fn name_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("name search");
+    for size in BANK_SIZES {
+        let bank = bank_with_customers(size);
+        let query = format!("customer{}", size / 2);
+
+        group.bench_with_input(BenchmarkId::new("index", size), &query, |b, query| {
+            b.iter(|| bank.search_customers(black_box(query)).len())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, phone_lookup, name_search);
+criterion_main!(benches);
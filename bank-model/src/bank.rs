@@ -0,0 +1,1419 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::card::{CardNumber, DEFAULT_BIN, validate_bin};
+use crate::errors::BankErrors;
+use crate::exchange::{ExchangeRate, ExchangeRates};
+use crate::index::CustomerIndex;
+use crate::journal::Journal;
+use crate::ledger::{Ledger, LedgerAccount};
+use crate::money::{Currency, Money};
+use crate::phone::PhoneNumber;
+use crate::storage::BankLock;
+
+pub type CustomerId = u64;
+pub type TransactionId = u64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BankState {
+    pub name: String,
+    customers: HashMap<CustomerId, Customer>,
+    accounts: HashMap<CardNumber, Account>,
+    //Phone numbers are unique, so a phone always resolves to exactly one customer
+    #[serde(skip)]
+    index: CustomerIndex,
+    next_customer_id: CustomerId,
+    //Every card the bank issues starts with this prefix
+    #[serde(default = "default_bin")]
+    card_bin: String,
+    transactions: Vec<Transaction>,
+    #[serde(default = "first_transaction_id")]
+    next_transaction_id: TransactionId,
+    //Double-entry books behind the balances, see `check_books`
+    #[serde(default)]
+    ledger: Ledger,
+    //Currency of amounts typed without a currency code
+    #[serde(default)]
+    currency: Currency,
+    #[serde(default)]
+    exchange_rates: ExchangeRates,
+    //Sequence number of the last journaled operation already applied to this state
+    #[serde(default)]
+    journal_seq: u64,
+    #[serde(skip)]
+    journal: Option<Journal>,
+    //Held as long as any copy of the bank is alive, see `storage::BankLock`
+    #[serde(skip)]
+    lock: Option<Arc<BankLock>>,
+}
+impl BankState {
+    pub fn new(bank_name: String) -> Self {
+        Self {
+            name: bank_name,
+            customers: HashMap::new(),
+            accounts: HashMap::new(),
+            index: CustomerIndex::default(),
+            next_customer_id: 1,
+            card_bin: DEFAULT_BIN.to_string(),
+            transactions: Vec::new(),
+            next_transaction_id: first_transaction_id(),
+            ledger: Ledger::default(),
+            currency: Currency::default(),
+            exchange_rates: ExchangeRates::default(),
+            journal_seq: 0,
+            journal: None,
+            lock: None,
+        }
+    }
+    pub fn get_customers(&self) -> &HashMap<CustomerId, Customer> {
+        &self.customers
+    }
+    pub fn get_accounts(&self) -> &HashMap<CardNumber, Account> {
+        &self.accounts
+    }
+    pub fn find_customer_by_phone(&self, phone: &PhoneNumber) -> Option<&Customer> {
+        self.index.by_phone(phone).and_then(|id| self.customers.get(&id))
+    }
+    pub fn find_card_by_phone(&self, phone: &PhoneNumber) -> Option<CardNumber> {
+        self.find_customer_by_phone(phone).map(|customer| customer.check_main_card())
+    }
+    //Customers sorted by id whose FIO matches every word of the query, see `CustomerIndex::search_name`
+    pub fn search_customers(&self, query: &str) -> Vec<&Customer> {
+        self.index.search_name(query).into_iter()
+            .filter_map(|id| self.customers.get(&id))
+            .collect()
+    }
+    //Customers number `page * per_page` onwards in id order, narrowed down by `query` unless it is empty,
+    //together with how many customers there are to page through
+    pub fn customers_page(&self, query: &str, page: usize, per_page: usize) -> (Vec<&Customer>, usize) {
+        let found;
+        let ids = if query.trim().is_empty() {
+            self.index.ids()
+        } else {
+            found = self.index.search_name(query);
+            &found
+        };
+        let customers = ids.iter()
+            .skip(page * per_page)
+            .take(per_page)
+            .filter_map(|id| self.customers.get(id))
+            .collect();
+        (customers, ids.len())
+    }
+    pub fn rebuild_indexes(&mut self) {
+        self.index = CustomerIndex::default();
+        for customer in self.customers.values() {
+            self.index.insert(customer);
+        }
+    }
+    //Snapshots written before the ledger existed have balances but no postings
+    pub(crate) fn open_ledger(&mut self) {
+        if !self.ledger.get_postings().is_empty() {
+            return
+        }
+        let mut cards: Vec<&CardNumber> = self.accounts.keys().collect();
+        cards.sort();
+        for card in cards {
+            for &balance in self.accounts[card].get_balances().filter(|balance| !balance.is_zero()) {
+                self.ledger.post_opening_balance(*card, balance);
+            }
+        }
+    }
+    pub fn get_customer_accounts<'a>(&'a self, customer: &'a Customer) -> impl Iterator<Item = &'a Account> {
+        customer.check_cards().iter().filter_map(|card| self.accounts.get(card))
+    }
+    pub fn get_exchange_rates(&self) -> &ExchangeRates {
+        &self.exchange_rates
+    }
+    pub fn set_exchange_rate(&mut self, from: String, to: String, rate: String) -> Result<(), BankErrors> {
+        let rate = ExchangeRate::parse(from.parse()?, to.parse()?, &rate)?;
+        self.execute(BankOperation::SetExchangeRate { rate }).map(|_| ())
+    }
+    pub fn get_card_bin(&self) -> &str {
+        &self.card_bin
+    }
+    pub fn set_card_bin(&mut self, bin: String) -> Result<(), BankErrors> {
+        validate_bin(bin.trim())?;
+        self.execute(BankOperation::SetCardBin { bin: bin.trim().to_string() }).map(|_| ())
+    }
+
+    //New customer together with their first card
+    pub fn create_user(&mut self, fio: String, phone_number: String, money_in_account: String) -> Result<(), BankErrors> {
+
+        let phone_number: PhoneNumber = phone_number.parse()?;
+        if self.index.by_phone(&phone_number).is_some() {
+            return Err(BankErrors::PhoneAlreadyRegistered(phone_number))
+        }
+        let new_card_number = self.free_card_number();
+        let correct_amount = Money::parse(&money_in_account, self.currency)?;
+
+        self.execute(BankOperation::CreateUser {
+            time: Utc::now(),
+            customer_id: self.next_customer_id,
+            fio,
+            phone_number,
+            card_number: new_card_number,
+            amount: correct_amount
+        }).map(|_| ())
+    }
+
+    //Changes FIO and/or phone of the customer who owns `card_number`, empty values are left as they were
+    pub fn update_customer(&mut self, card_number: String, fio: String, phone_number: String) -> Result<(), BankErrors> {
+        let card = convert_card_num(card_number)?;
+        let customer_id = self.find_account(card)?.check_owner();
+        let fio = Some(fio.trim().to_string()).filter(|fio| !fio.is_empty());
+        let phone_number = match phone_number.trim() {
+            "" => None,
+            phone => Some(phone.parse::<PhoneNumber>()?)
+        };
+
+        self.execute(BankOperation::UpdateCustomer { customer_id, fio, phone_number }).map(|_| ())
+    }
+
+    //One more card for the customer who owns `owner_card`
+    pub fn open_account(&mut self, owner_card: String, money_in_account: String) -> Result<CardNumber, BankErrors> {
+        let owner_card = convert_card_num(owner_card)?;
+        let owner = self.find_account(owner_card)?.check_owner();
+        let correct_amount = Money::parse(&money_in_account, self.currency)?;
+        let new_card_number = self.free_card_number();
+
+        self.execute(BankOperation::OpenAccount {
+            time: Utc::now(),
+            customer_id: owner,
+            card_number: new_card_number,
+            amount: correct_amount
+        })?;
+        Ok(new_card_number)
+    }
+
+    //Closes a single card, the customer goes away together with their last card
+    pub fn close_account(&mut self, card_number: String) -> Result<(), BankErrors> {
+        let card = convert_card_num(card_number)?;
+        self.execute(BankOperation::CloseAccount { time: Utc::now(), card_number: card }).map(|_| ())
+    }
+
+    //Removes the customer owning `card_number` with all of their cards
+    pub fn delete_user(&mut self, card_number: String) -> Result<(), BankErrors> {
+        let card = convert_card_num(card_number)?;
+        self.execute(BankOperation::DeleteUser { time: Utc::now(), card_number: card }).map(|_| ())
+    }
+
+    pub fn transfer_by_phone(&mut self, sender_card: String, recipient_phone: String, amount: String) -> Result<Transaction, BankErrors> {
+
+        let converted_sen_card = convert_card_num(sender_card)?;
+        let correct_amount = self.convert_transfer_amount(converted_sen_card, &amount)?;
+        let converted_phone: PhoneNumber = recipient_phone.parse()?;
+
+        //Money sent by phone lands on the customer's first card.
+        //An unknown phone is still recorded, as a failed transfer
+        let recipient_card = self.find_card_by_phone(&converted_phone);
+        self.execute_transfer(converted_sen_card, Recipient::Phone(converted_phone), recipient_card, correct_amount)
+    }
+
+    pub fn transfer_by_card(&mut self, sender_card: String, recipient_card: String, amount: String) -> Result<Transaction, BankErrors> {
+
+        let converted_sen_card = convert_card_num(sender_card)?;
+        let correct_amount = self.convert_transfer_amount(converted_sen_card, &amount)?;
+        let converted_rec_card = convert_card_num(recipient_card)?;
+
+        self.execute_transfer(converted_sen_card, Recipient::Card(converted_rec_card), Some(converted_rec_card), correct_amount)
+    }
+
+    //Same as a card transfer, but both cards have to belong to one customer
+    pub fn transfer_between_own_accounts(&mut self, from_card: String, to_card: String, amount: String) -> Result<Transaction, BankErrors> {
+
+        let converted_from_card = convert_card_num(from_card)?;
+        let converted_to_card = convert_card_num(to_card)?;
+        let owner = self.accounts.get(&converted_from_card).ok_or(BankErrors::SenderNotFound(converted_from_card))?.check_owner();
+        let recipient_owner = self.accounts.get(&converted_to_card)
+            .ok_or(BankErrors::RecipientNotFound(converted_to_card.to_string()))?
+            .check_owner();
+        if owner != recipient_owner {
+            return Err(BankErrors::NotOwnAccount(converted_to_card))
+        }
+        let correct_amount = self.convert_transfer_amount(converted_from_card, &amount)?;
+
+        self.execute_transfer(converted_from_card, Recipient::Internal(converted_to_card), Some(converted_to_card), correct_amount)
+    }
+
+    //Undoes a completed transfer by moving the credited money back from the recipient,
+    //the refund is a new transaction linked to the original one
+    pub fn reverse_transaction(&mut self, id: TransactionId) -> Result<Transaction, BankErrors> {
+        let tx = self.execute(BankOperation::Reverse { time: Utc::now(), transaction_id: id })?;
+        Ok(tx.expect("reversal always records a transaction"))
+    }
+
+    //Cash brought to the bank, credited to the card
+    pub fn deposit(&mut self, card_number: String, amount: String) -> Result<Transaction, BankErrors> {
+        let card = convert_card_num(card_number)?;
+        let correct_amount = self.convert_transfer_amount(card, &amount)?;
+        let tx = self.execute(BankOperation::Deposit { time: Utc::now(), card_number: card, amount: correct_amount })?;
+        Ok(tx.expect("deposit always records a transaction"))
+    }
+
+    //Cash paid out from the card
+    pub fn withdraw(&mut self, card_number: String, amount: String) -> Result<Transaction, BankErrors> {
+        let card = convert_card_num(card_number)?;
+        let correct_amount = self.convert_transfer_amount(card, &amount)?;
+        let tx = self.execute(BankOperation::Withdrawal { time: Utc::now(), card_number: card, amount: correct_amount })?;
+        Ok(tx.expect("withdrawal always records a transaction"))
+    }
+
+    //Amounts without a currency code are taken in the card's currency
+    fn convert_transfer_amount(&self, sender_card: CardNumber, amount: &str) -> Result<Money, BankErrors> {
+        let currency = self.accounts.get(&sender_card).map_or(self.currency, |account| account.check_currency());
+        let amount = Money::parse(amount, currency)?;
+        if amount.is_zero() {
+            return Err(BankErrors::InvalidAmountProblem)
+        }
+        Ok(amount)
+    }
+
+    fn execute_transfer(&mut self, sender_card: CardNumber, recipient: Recipient, recipient_card: Option<CardNumber>, amount: Money) -> Result<Transaction, BankErrors> {
+        let tx = self.execute(BankOperation::Transfer {
+            time: Utc::now(),
+            sender_card,
+            recipient,
+            recipient_card,
+            amount
+        })?;
+        Ok(tx.expect("transfer always records a transaction"))
+    }
+
+    //The recipient is always credited in their own currency, converted at the bank's rate if needed
+    fn move_money(&mut self, sender_card: CardNumber, recipient: &Recipient, recipient_card: Option<CardNumber>, amount: Money) -> Result<(Money, Option<ExchangeRate>), BankErrors> {
+        let recipient_account = match recipient_card.and_then(|card| self.accounts.get(&card)) {
+            Some(account) => account,
+            None => return Err(BankErrors::RecipientNotFound(recipient.to_string()))
+        };
+
+        let target_currency = recipient_account.check_currency();
+        let (credited, rate) = if amount.currency() == target_currency {
+            (amount, None)
+        } else {
+            let rate = self.exchange_rates.rate(amount.currency(), target_currency)?;
+            (rate.convert(amount)?, Some(rate))
+        };
+
+        self.move_exact(sender_card, amount, recipient_account.check_card_number(), credited)?;
+        Ok((credited, rate))
+    }
+
+    //Transfer engine: both parties are updated on copies first and only stored when both succeeded,
+    //so a failed transfer never leaves the bank with money debited but not credited
+    fn move_exact(&mut self, sender_card: CardNumber, debit: Money, recipient_card: CardNumber, credit: Money) -> Result<(), BankErrors> {
+        let mut sender = self.accounts.get(&sender_card).ok_or(BankErrors::SenderNotFound(sender_card))?.clone();
+        let recipient = self.accounts.get(&recipient_card).ok_or(BankErrors::RecipientNotFound(recipient_card.to_string()))?;
+
+        sender.refuse_money(debit)?;
+        if sender_card == recipient_card {
+            sender.receive_money(credit)?;
+        } else {
+            let mut recipient = recipient.clone();
+            recipient.receive_money(credit)?;
+            self.accounts.insert(recipient_card, recipient);
+        }
+        self.accounts.insert(sender_card, sender);
+        Ok(())
+    }
+
+    //The refund goes back exactly as the money came: the credited amount is taken from the recipient
+    //and the original amount is returned to the sender, whatever the exchange rates are today
+    fn reverse(&mut self, time: DateTime<Utc>, id: TransactionId) -> Result<Transaction, BankErrors> {
+        let original = self.transactions.binary_search_by_key(&id, |tx| tx.id)
+            .map(|position| &self.transactions[position])
+            .map_err(|_| BankErrors::TransactionNotFound(id))?;
+        let refund_from = match original.recipient_card {
+            Some(card) if original.is_reversible() => card,
+            _ => return Err(BankErrors::NotReversible(id))
+        };
+        let refund_to = original.sender_card.ok_or(BankErrors::NotReversible(id))?;
+        let (debit, credit) = (original.credited_amount, original.amount);
+        let rate = original.exchange_rate.and_then(|rate| rate.inverse().ok());
+
+        let mut refund = self.new_transaction(TransactionKind::Transfer, time, debit, Some(refund_from), Recipient::Card(refund_to), Some(refund_to));
+        refund.reverses = Some(id);
+        let result = self.move_exact(refund_from, debit, refund_to, credit);
+        match result {
+            Ok(()) => {
+                refund.complete(credit, rate);
+                self.ledger.post_transaction(&refund);
+                if let Ok(position) = self.transactions.binary_search_by_key(&id, |tx| tx.id) {
+                    self.transactions[position].status = TransactionStatus::Reversed;
+                    self.transactions[position].reversed_by = Some(refund.id);
+                }
+            },
+            Err(_) => refund.status = TransactionStatus::Failed
+        }
+        self.transactions.push(refund.clone());
+        result.map(|_| refund)
+    }
+
+    fn put_money(&mut self, card_number: CardNumber, amount: Money) -> Result<(), BankErrors> {
+        self.accounts.get_mut(&card_number)
+            .ok_or(BankErrors::RecipientNotFound(card_number.to_string()))?
+            .receive_money(amount)
+    }
+
+    fn take_money(&mut self, card_number: CardNumber, amount: Money) -> Result<(), BankErrors> {
+        self.accounts.get_mut(&card_number)
+            .ok_or(BankErrors::SenderNotFound(card_number))?
+            .refuse_money(amount)
+    }
+
+    fn new_transaction(&mut self, kind: TransactionKind, time: DateTime<Utc>, amount: Money, sender_card: Option<CardNumber>, recipient: Recipient, recipient_card: Option<CardNumber>) -> Transaction {
+        let tx = Transaction::new(self.next_transaction_id, kind, time, amount, sender_card, recipient, recipient_card);
+        self.next_transaction_id += 1;
+        tx
+    }
+
+    //Every attempt is stored, the failed ones too
+    fn record(&mut self, mut tx: Transaction, result: Result<(Money, Option<ExchangeRate>), BankErrors>) -> Result<Option<Transaction>, BankErrors> {
+        match result {
+            Ok((credited, rate)) => {
+                tx.complete(credited, rate);
+                self.ledger.post_transaction(&tx);
+            },
+            Err(_) => tx.status = TransactionStatus::Failed
+        }
+        self.transactions.push(tx.clone());
+        result.map(|_| Some(tx))
+    }
+
+    //Opening balances are recorded as deposits, so every kopeck on an account has a transaction behind it
+    fn open_with_deposit(&mut self, time: DateTime<Utc>, card_number: CardNumber, owner: CustomerId, amount: Money) -> Result<(), BankErrors> {
+        self.accounts.insert(card_number, Account::new(card_number, owner, amount.currency()));
+        if amount.is_zero() {
+            return Ok(())
+        }
+        let tx = self.new_transaction(TransactionKind::Deposit, time, amount, None, Recipient::Card(card_number), Some(card_number));
+        let result = self.put_money(card_number, amount).map(|_| (amount, None));
+        self.record(tx, result).map(|_| ())
+    }
+
+    //Whatever is left on a closed card is paid out, so money never disappears from the books
+    fn remove_account(&mut self, time: DateTime<Utc>, card_number: CardNumber) {
+        if let Some(account) = self.accounts.remove(&card_number) {
+            for &left in account.get_balances().filter(|left| !left.is_zero()) {
+                let tx = self.new_transaction(TransactionKind::Withdrawal, time, left, Some(card_number), Recipient::Cash, None);
+                let _ = self.record(tx, Ok((left, None)));
+            }
+        }
+    }
+
+    fn remove_customer(&mut self, time: DateTime<Utc>, id: CustomerId) {
+        if let Some(customer) = self.customers.remove(&id) {
+            self.index.remove(&customer);
+            for card in customer.cards {
+                self.remove_account(time, card);
+            }
+        }
+    }
+
+    //Audit: debits equal credits in every currency and each card holds exactly what the ledger says
+    pub fn check_books(&self) -> Result<(), BankErrors> {
+        if let Some((&currency, _)) = self.ledger.trial_balance().iter().find(|(_, totals)| !totals.is_balanced()) {
+            return Err(BankErrors::LedgerUnbalanced(currency))
+        }
+        let balances = self.ledger.balances();
+        for (card, account) in &self.accounts {
+            for currency in Currency::ALL {
+                let ledger = balances.get(&(LedgerAccount::Card(*card), currency)).copied().unwrap_or(0);
+                if ledger != account.check_balance_in(currency).minor_units() as i128 {
+                    return Err(BankErrors::LedgerMismatch { card: *card, currency })
+                }
+            }
+        }
+        for (&(ledger_account, currency), &balance) in &balances {
+            if let LedgerAccount::Card(card) = ledger_account {
+                if balance != 0 && !self.accounts.contains_key(&card) {
+                    return Err(BankErrors::LedgerMismatch { card, currency })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    fn find_account(&self, card_number: CardNumber) -> Result<&Account, BankErrors> {
+        self.accounts.get(&card_number)
+            .ok_or(BankErrors::CantFindUserByCard(format!("Cant find user with card number {card_number}")))
+    }
+
+    fn free_card_number(&self) -> CardNumber {
+        let mut new_card_number = CardNumber::generate(&self.card_bin);
+
+        while self.accounts.contains_key(&new_card_number) {
+            new_card_number = CardNumber::generate(&self.card_bin);
+        }
+        new_card_number
+    }
+
+    pub fn get_transactions(&self) -> &Vec<Transaction> {
+        &self.transactions
+    }
+
+    //Grows with every operation, so two readings tell whether the bank changed in between
+    pub fn last_operation(&self) -> u64 {
+        self.journal_seq
+    }
+
+    pub(crate) fn attach_journal(&mut self, journal: Journal, lock: Arc<BankLock>) {
+        self.journal = Some(journal);
+        self.lock = Some(lock);
+    }
+
+    //Lets go of the files as if the process had exited, the state itself stays in memory
+    #[cfg(test)]
+    pub(crate) fn close(&mut self) {
+        self.journal = None;
+        self.lock = None;
+    }
+
+    pub(crate) fn journal_mut(&mut self) -> Option<&mut Journal> {
+        self.journal.as_mut()
+    }
+
+    //Every mutation goes through here: it is written to the journal first and applied afterwards.
+    //Operations carry everything that is random or time dependent, so replaying the journal
+    //reproduces exactly the same state, including operations that were rejected
+    fn execute(&mut self, op: BankOperation) -> Result<Option<Transaction>, BankErrors> {
+        let seq = self.journal_seq + 1;
+        if let Some(journal) = self.journal.as_mut() {
+            journal.append(seq, &op)?;
+        }
+        self.journal_seq = seq;
+        self.apply(op)
+    }
+
+    pub(crate) fn replay(&mut self, seq: u64, op: BankOperation) {
+        if seq <= self.journal_seq {
+            return
+        }
+        self.journal_seq = seq;
+        //Rejected operations were rejected the first time as well, nothing to do about them
+        let _ = self.apply(op);
+    }
+
+    fn apply(&mut self, op: BankOperation) -> Result<Option<Transaction>, BankErrors> {
+        match op {
+            BankOperation::CreateUser { time, customer_id, fio, phone_number, card_number, amount } => {
+                if self.index.by_phone(&phone_number).is_some() {
+                    return Err(BankErrors::PhoneAlreadyRegistered(phone_number))
+                }
+                let new_customer = Customer {
+                    id: customer_id,
+                    fio,
+                    phone_number,
+                    cards: vec![card_number],
+                };
+                self.index.insert(&new_customer);
+                self.customers.insert(customer_id, new_customer);
+                self.next_customer_id = self.next_customer_id.max(customer_id + 1);
+                self.open_with_deposit(time, card_number, customer_id, amount)?;
+                Ok(None)
+            }
+
+            BankOperation::UpdateCustomer { customer_id, fio, phone_number } => {
+                if let Some(phone) = &phone_number {
+                    if self.index.by_phone(phone).is_some_and(|owner| owner != customer_id) {
+                        return Err(BankErrors::PhoneAlreadyRegistered(phone.clone()))
+                    }
+                }
+                let customer = self.customers.get_mut(&customer_id)
+                    .ok_or(BankErrors::CantFindUserByCard(format!("Cant find customer {customer_id}")))?;
+
+                self.index.remove(customer);
+                if let Some(fio) = fio {
+                    customer.fio = fio;
+                }
+                if let Some(phone_number) = phone_number {
+                    customer.phone_number = phone_number;
+                }
+                self.index.insert(customer);
+                Ok(None)
+            }
+
+            BankOperation::OpenAccount { time, customer_id, card_number, amount } => {
+                let customer = self.customers.get_mut(&customer_id)
+                    .ok_or(BankErrors::CantFindUserByCard(format!("Cant find customer {customer_id}")))?;
+                customer.cards.push(card_number);
+                self.open_with_deposit(time, card_number, customer_id, amount)?;
+                Ok(None)
+            }
+
+            BankOperation::CloseAccount { time, card_number } => {
+                let owner = self.find_account(card_number)?.check_owner();
+                self.remove_account(time, card_number);
+                let last_card = match self.customers.get_mut(&owner) {
+                    Some(customer) => {
+                        customer.cards.retain(|card| *card != card_number);
+                        customer.cards.is_empty()
+                    },
+                    None => false
+                };
+                if last_card {
+                    self.remove_customer(time, owner);
+                }
+                Ok(None)
+            }
+
+            BankOperation::DeleteUser { time, card_number } => {
+                let owner = self.find_account(card_number)?.check_owner();
+                self.remove_customer(time, owner);
+                Ok(None)
+            }
+
+            BankOperation::Transfer { time, sender_card, recipient, recipient_card, amount } => {
+                let tx = self.new_transaction(TransactionKind::Transfer, time, amount, Some(sender_card), recipient, recipient_card);
+                let result = self.move_money(sender_card, &tx.recipient, recipient_card, amount);
+                self.record(tx, result)
+            }
+
+            BankOperation::Deposit { time, card_number, amount } => {
+                let tx = self.new_transaction(TransactionKind::Deposit, time, amount, None, Recipient::Card(card_number), Some(card_number));
+                let result = self.put_money(card_number, amount).map(|_| (amount, None));
+                self.record(tx, result)
+            }
+
+            BankOperation::Withdrawal { time, card_number, amount } => {
+                let tx = self.new_transaction(TransactionKind::Withdrawal, time, amount, Some(card_number), Recipient::Cash, None);
+                let result = self.take_money(card_number, amount).map(|_| (amount, None));
+                self.record(tx, result)
+            }
+
+            BankOperation::Reverse { time, transaction_id } => {
+                self.reverse(time, transaction_id).map(Some)
+            }
+
+            BankOperation::SetExchangeRate { rate } => {
+                self.exchange_rates.set_rate(rate);
+                Ok(None)
+            }
+
+            BankOperation::SetCardBin { bin } => {
+                self.card_bin = bin;
+                Ok(None)
+            }
+        }
+    }
+}
+
+//A single mutation of the bank, as it is written to the journal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BankOperation {
+    CreateUser { time: DateTime<Utc>, customer_id: CustomerId, fio: String, phone_number: PhoneNumber, card_number: CardNumber, amount: Money },
+    UpdateCustomer { customer_id: CustomerId, fio: Option<String>, phone_number: Option<PhoneNumber> },
+    OpenAccount { time: DateTime<Utc>, customer_id: CustomerId, card_number: CardNumber, amount: Money },
+    CloseAccount { time: DateTime<Utc>, card_number: CardNumber },
+    DeleteUser { time: DateTime<Utc>, card_number: CardNumber },
+    Transfer { time: DateTime<Utc>, sender_card: CardNumber, recipient: Recipient, recipient_card: Option<CardNumber>, amount: Money },
+    Deposit { time: DateTime<Utc>, card_number: CardNumber, amount: Money },
+    Withdrawal { time: DateTime<Utc>, card_number: CardNumber, amount: Money },
+    Reverse { time: DateTime<Utc>, transaction_id: TransactionId },
+    SetExchangeRate { rate: ExchangeRate },
+    SetCardBin { bin: String },
+}
+
+//The person: one customer owns one or more cards
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Customer {
+    id: CustomerId,
+    fio: String,
+    phone_number: PhoneNumber,
+    cards: Vec<CardNumber>,
+}
+impl Customer {
+    pub fn get_id(&self) -> CustomerId {
+        self.id
+    }
+    pub fn get_phone_number(&self) -> &PhoneNumber {
+        &self.phone_number
+    }
+}
+impl BankUser for Customer {
+    fn check_fio(&self) -> &str {
+        &self.fio
+    }
+    fn check_phone(&self) -> &str { self.phone_number.as_str() }
+    fn check_cards(&self) -> &[CardNumber] {
+        &self.cards
+    }
+    fn check_main_card(&self) -> CardNumber {
+        self.cards[0]
+    }
+}
+
+//A card with its own balances
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    card_number: CardNumber,
+    owner: CustomerId,
+    //Main currency of the account: incoming transfers are credited in it
+    currency: Currency,
+    balances: BTreeMap<Currency, Money>,
+}
+impl Account {
+    fn new(card_number: CardNumber, owner: CustomerId, currency: Currency) -> Self {
+        Self {
+            card_number,
+            owner,
+            currency,
+            balances: BTreeMap::from([(currency, Money::new(0, currency))]),
+        }
+    }
+    pub fn get_balances(&self) -> impl Iterator<Item = &Money> {
+        self.balances.values()
+    }
+}
+impl BankAccount for Account {
+    fn check_card_number(&self) -> CardNumber {
+        self.card_number
+    }
+    fn check_owner(&self) -> CustomerId {
+        self.owner
+    }
+    fn check_currency(&self) -> Currency {
+        self.currency
+    }
+    fn check_balance(&self) -> Money {
+        self.check_balance_in(self.currency)
+    }
+    fn check_balance_in(&self, currency: Currency) -> Money {
+        self.balances.get(&currency).copied().unwrap_or(Money::new(0, currency))
+    }
+    fn receive_money(&mut self, amount: Money) -> Result<(), BankErrors> {
+        let balance = self.check_balance_in(amount.currency()).checked_add(amount)?;
+        self.balances.insert(amount.currency(), balance);
+        Ok(())
+    }
+    fn refuse_money(&mut self, amount: Money) -> Result<(), BankErrors> {
+        let balance = self.check_balance_in(amount.currency()).checked_sub(amount)?;
+        self.balances.insert(amount.currency(), balance);
+        Ok(())
+    }
+}
+
+//Who the money was addressed to, as the sender gave it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Recipient {
+    Card(CardNumber),
+    Phone(PhoneNumber),
+    //Another card of the sender
+    Internal(CardNumber),
+    //Money paid out over the counter
+    Cash,
+}
+impl fmt::Display for Recipient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recipient::Card(card) | Recipient::Internal(card) => write!(f, "{card}"),
+            Recipient::Phone(phone) => write!(f, "{phone}"),
+            Recipient::Cash => write!(f, "cash")
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionKind {
+    Transfer,
+    Deposit,
+    Withdrawal,
+    Fee,
+}
+impl TransactionKind {
+    pub fn code(&self) -> &'static str {
+        match self {
+            TransactionKind::Transfer => "transfer",
+            TransactionKind::Deposit => "deposit",
+            TransactionKind::Withdrawal => "withdrawal",
+            TransactionKind::Fee => "fee",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionStatus {
+    Pending,
+    Completed,
+    Failed,
+    Reversed,
+}
+impl TransactionStatus {
+    pub fn code(&self) -> &'static str {
+        match self {
+            TransactionStatus::Pending => "pending",
+            TransactionStatus::Completed => "completed",
+            TransactionStatus::Failed => "failed",
+            TransactionStatus::Reversed => "reversed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    id: TransactionId,
+    kind: TransactionKind,
+    status: TransactionStatus,
+    time_of_creation: DateTime<Utc>,
+    amount: Money,
+    //None for deposits, the money came from outside the bank
+    sender_card: Option<CardNumber>,
+    recipient: Recipient,
+    //Card that was (or would have been) credited, None when the recipient could not be resolved
+    recipient_card: Option<CardNumber>,
+    //What the recipient actually got, differs from `amount` when currencies were converted
+    credited_amount: Money,
+    exchange_rate: Option<ExchangeRate>,
+    //Links between a transfer and the refund that undid it
+    #[serde(default)]
+    reverses: Option<TransactionId>,
+    #[serde(default)]
+    reversed_by: Option<TransactionId>
+}
+impl Transaction {
+    fn new(id: TransactionId, kind: TransactionKind, time: DateTime<Utc>, amount: Money, sender: Option<CardNumber>, recipient: Recipient, recipient_card: Option<CardNumber>) -> Self {
+        Self {
+            id,
+            kind,
+            status: TransactionStatus::Pending,
+            time_of_creation: time,
+            amount,
+            sender_card: sender,
+            recipient,
+            recipient_card,
+            credited_amount: Money::new(0, amount.currency()),
+            exchange_rate: None,
+            reverses: None,
+            reversed_by: None
+        }
+    }
+    fn complete(&mut self, credited: Money, rate: Option<ExchangeRate>) {
+        self.status = TransactionStatus::Completed;
+        self.credited_amount = credited;
+        self.exchange_rate = rate;
+    }
+    pub fn get_id(&self) -> TransactionId {
+        self.id
+    }
+    pub fn get_kind(&self) -> TransactionKind {
+        self.kind
+    }
+    pub fn get_status(&self) -> TransactionStatus {
+        self.status
+    }
+    pub fn get_tx_time(&self) -> DateTime<Utc> {
+        self.time_of_creation
+    }
+    pub fn get_amount(&self) -> Money {
+        self.amount
+    }
+    pub fn get_sender_card(&self) -> Option<CardNumber> {
+        self.sender_card
+    }
+    pub fn get_recipient(&self) -> &Recipient {
+        &self.recipient
+    }
+    pub fn get_recipient_card(&self) -> Option<CardNumber> {
+        self.recipient_card
+    }
+    //The card sent or received the money
+    pub fn involves(&self, card: CardNumber) -> bool {
+        self.sender_card == Some(card) || self.recipient_card == Some(card)
+    }
+    pub fn get_credited_amount(&self) -> Money {
+        self.credited_amount
+    }
+    pub fn get_exchange_rate(&self) -> Option<ExchangeRate> {
+        self.exchange_rate
+    }
+    pub fn get_reverses(&self) -> Option<TransactionId> {
+        self.reverses
+    }
+    pub fn get_reversed_by(&self) -> Option<TransactionId> {
+        self.reversed_by
+    }
+    pub fn is_reversible(&self) -> bool {
+        self.kind == TransactionKind::Transfer
+            && self.status == TransactionStatus::Completed
+            && self.reverses.is_none()
+    }
+}
+
+fn convert_card_num(card_num: String) -> Result<CardNumber, BankErrors> {
+    card_num.trim().parse::<CardNumber>()
+}
+
+fn first_transaction_id() -> TransactionId {
+    1
+}
+
+fn default_bin() -> String {
+    DEFAULT_BIN.to_string()
+}
+
+pub trait BankUser {
+    fn check_fio(&self) -> &str;
+    fn check_phone(&self) -> &str;
+    fn check_cards(&self) -> &[CardNumber];
+    fn check_main_card(&self) -> CardNumber;
+}
+
+pub trait BankAccount {
+    fn check_card_number(&self) -> CardNumber;
+    fn check_owner(&self) -> CustomerId;
+    fn check_currency(&self) -> Currency;
+    fn check_balance(&self) -> Money;
+    fn check_balance_in(&self, currency: Currency) -> Money;
+    fn receive_money(&mut self, amount: Money) -> Result<(), BankErrors>;
+    fn refuse_money(&mut self, amount: Money) -> Result<(), BankErrors>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{bank_with_two_users, card_of, rub, total_money};
+
+    fn balance(bank: &BankState, card: CardNumber) -> Money {
+        bank.accounts[&card].check_balance()
+    }
+
+    fn unused_card(bank: &BankState) -> CardNumber {
+        std::iter::repeat_with(|| CardNumber::generate(DEFAULT_BIN))
+            .find(|card| !bank.accounts.contains_key(card))
+            .unwrap()
+    }
+
+    #[test]
+    fn transfer_by_card_moves_money() {
+        let (mut bank, first, second) = bank_with_two_users();
+        let tx = bank.transfer_by_card(first.to_string(), second.to_string(), "30".to_string()).unwrap();
+
+        assert_eq!(tx.get_amount(), rub(30));
+        assert_eq!(balance(&bank, first), rub(70));
+        assert_eq!(balance(&bank, second), rub(80));
+        assert_eq!(total_money(&bank), rub(150));
+    }
+
+    #[test]
+    fn transfer_by_phone_moves_money() {
+        let (mut bank, first, second) = bank_with_two_users();
+        bank.transfer_by_phone(second.to_string(), "+79000000111".to_string(), "50".to_string()).unwrap();
+
+        assert_eq!(balance(&bank, first), rub(150));
+        assert_eq!(balance(&bank, second), rub(0));
+        assert_eq!(total_money(&bank), rub(150));
+    }
+
+    #[test]
+    fn unknown_recipient_card_keeps_balances() {
+        let (mut bank, first, second) = bank_with_two_users();
+        let missing = unused_card(&bank);
+        let result = bank.transfer_by_card(first.to_string(), missing.to_string(), "30".to_string());
+
+        assert_eq!(result.unwrap_err(), BankErrors::RecipientNotFound(missing.to_string()));
+        assert_eq!(balance(&bank, first), rub(100));
+        assert_eq!(balance(&bank, second), rub(50));
+    }
+
+    #[test]
+    fn unknown_recipient_phone_keeps_balances() {
+        let (mut bank, first, second) = bank_with_two_users();
+        let result = bank.transfer_by_phone(first.to_string(), "+79000000333".to_string(), "30".to_string());
+
+        assert_eq!(result.unwrap_err(), BankErrors::RecipientNotFound("+79000000333".to_string()));
+        assert_eq!(balance(&bank, first), rub(100));
+        assert_eq!(balance(&bank, second), rub(50));
+    }
+
+    #[test]
+    fn unknown_sender_keeps_balances() {
+        let (mut bank, first, second) = bank_with_two_users();
+        let missing = unused_card(&bank);
+        let result = bank.transfer_by_card(missing.to_string(), second.to_string(), "30".to_string());
+
+        assert_eq!(result.unwrap_err(), BankErrors::SenderNotFound(missing));
+        assert_eq!(balance(&bank, first), rub(100));
+        assert_eq!(balance(&bank, second), rub(50));
+    }
+
+    #[test]
+    fn insufficient_funds_keeps_balances() {
+        let (mut bank, first, second) = bank_with_two_users();
+        let result = bank.transfer_by_card(second.to_string(), first.to_string(), "51".to_string());
+
+        assert_eq!(result.unwrap_err(), BankErrors::NotEnoughMoney { available: rub(50), requested: rub(51) });
+        assert_eq!(balance(&bank, first), rub(100));
+        assert_eq!(balance(&bank, second), rub(50));
+    }
+
+    #[test]
+    fn invalid_input_keeps_balances() {
+        let (mut bank, first, second) = bank_with_two_users();
+
+        assert_eq!(
+            bank.transfer_by_card(first.to_string(), second.to_string(), "ten".to_string()).unwrap_err(),
+            BankErrors::InvalidAmountProblem
+        );
+        assert_eq!(
+            bank.transfer_by_card(first.to_string(), "abc".to_string(), "10".to_string()).unwrap_err(),
+            BankErrors::InvalidCardProblem("abc".to_string())
+        );
+        assert_eq!(balance(&bank, first), rub(100));
+        assert_eq!(balance(&bank, second), rub(50));
+    }
+
+    #[test]
+    fn transfer_to_self_keeps_balance() {
+        let (mut bank, first, _) = bank_with_two_users();
+        bank.transfer_by_card(first.to_string(), first.to_string(), "100".to_string()).unwrap();
+
+        assert_eq!(balance(&bank, first), rub(100));
+        assert_eq!(total_money(&bank), rub(150));
+    }
+
+    #[test]
+    fn transfer_accepts_minor_units() {
+        let (mut bank, first, second) = bank_with_two_users();
+        bank.transfer_by_card(first.to_string(), second.to_string(), "0.75".to_string()).unwrap();
+
+        assert_eq!(balance(&bank, first), Money::new(9925, Currency::Rub));
+        assert_eq!(balance(&bank, second), Money::new(5075, Currency::Rub));
+        assert_eq!(total_money(&bank), rub(150));
+    }
+
+    #[test]
+    fn zero_or_missing_currency_amount_is_rejected() {
+        let (mut bank, first, second) = bank_with_two_users();
+
+        assert_eq!(
+            bank.transfer_by_card(first.to_string(), second.to_string(), "0".to_string()).unwrap_err(),
+            BankErrors::InvalidAmountProblem
+        );
+        assert_eq!(
+            bank.transfer_by_card(first.to_string(), second.to_string(), "10 USD".to_string()).unwrap_err(),
+            BankErrors::NotEnoughMoney { available: Money::new(0, Currency::Usd), requested: Money::new(1000, Currency::Usd) }
+        );
+        assert_eq!(balance(&bank, first), rub(100));
+        assert_eq!(balance(&bank, second), rub(50));
+    }
+
+    #[test]
+    fn overflowing_credit_keeps_balances() {
+        let (mut bank, first, _) = bank_with_two_users();
+        bank.create_user("Rich R.R.".to_string(), "+79000000333".to_string(), "184467440737095515".to_string()).unwrap();
+        let rich = card_of(&bank, "+79000000333");
+
+        assert_eq!(
+            bank.transfer_by_card(first.to_string(), rich.to_string(), "100".to_string()).unwrap_err(),
+            BankErrors::AmountOverflow
+        );
+        assert_eq!(balance(&bank, first), rub(100));
+        assert_eq!(balance(&bank, rich), rub(184467440737095515));
+    }
+
+    #[test]
+    fn transfer_between_currencies_converts_at_bank_rate() {
+        let (mut bank, first, _) = bank_with_two_users();
+        bank.create_user("Smith J.".to_string(), "+79000000444".to_string(), "10 USD".to_string()).unwrap();
+        let dollar = card_of(&bank, "+79000000444");
+        bank.set_exchange_rate("USD".to_string(), "RUB".to_string(), "90".to_string()).unwrap();
+
+        let tx = bank.transfer_by_card(first.to_string(), dollar.to_string(), "45".to_string()).unwrap();
+        assert_eq!(tx.get_amount(), rub(45));
+        assert_eq!(tx.get_credited_amount(), Money::new(49, Currency::Usd));
+        assert_eq!(tx.get_exchange_rate().unwrap().to_string(), "1 RUB = 0.011111 USD");
+        assert_eq!(balance(&bank, first), rub(55));
+        assert_eq!(balance(&bank, dollar), Money::new(1049, Currency::Usd));
+
+        let tx = bank.transfer_by_card(dollar.to_string(), first.to_string(), "1.50".to_string()).unwrap();
+        assert_eq!(tx.get_credited_amount(), rub(135));
+        assert_eq!(tx.get_exchange_rate().unwrap().to_string(), "1 USD = 90.00 RUB");
+        assert_eq!(balance(&bank, first), rub(190));
+        assert_eq!(balance(&bank, dollar), Money::new(899, Currency::Usd));
+    }
+
+    #[test]
+    fn foreign_balance_can_be_spent_and_converted_to_own_currency() {
+        let (mut bank, first, second) = bank_with_two_users();
+        bank.create_user("Smith J.".to_string(), "+79000000444".to_string(), "10 USD".to_string()).unwrap();
+        let dollar = card_of(&bank, "+79000000444");
+        bank.transfer_by_card(dollar.to_string(), first.to_string(), "2 USD".to_string()).unwrap();
+
+        //Recipient is credited in its main currency, the foreign balance stays untouched
+        assert_eq!(bank.accounts[&first].check_balance_in(Currency::Usd), Money::new(0, Currency::Usd));
+
+        bank.accounts.get_mut(&first).unwrap().receive_money(Money::new(300, Currency::Usd)).unwrap();
+        bank.transfer_by_card(first.to_string(), first.to_string(), "3 USD".to_string()).unwrap();
+        assert_eq!(bank.accounts[&first].check_balance_in(Currency::Usd), Money::new(0, Currency::Usd));
+        assert_eq!(balance(&bank, first), rub(100 + 180 + 270));
+
+        let result = bank.transfer_by_card(second.to_string(), first.to_string(), "1 USD".to_string());
+        assert_eq!(
+            result.unwrap_err(),
+            BankErrors::NotEnoughMoney { available: Money::new(0, Currency::Usd), requested: Money::new(100, Currency::Usd) }
+        );
+    }
+
+    #[test]
+    fn customer_can_own_several_cards() {
+        let (mut bank, first, _) = bank_with_two_users();
+        let second_card = bank.open_account(first.to_string(), "20 USD".to_string()).unwrap();
+
+        let owner = &bank.customers[&bank.accounts[&first].check_owner()];
+        assert_eq!(owner.check_cards(), &[first, second_card]);
+        assert_eq!(bank.accounts[&second_card].check_owner(), owner.get_id());
+        assert_eq!(balance(&bank, second_card), Money::new(2000, Currency::Usd));
+        assert_eq!(bank.get_customer_accounts(owner).count(), 2);
+    }
+
+    #[test]
+    fn transfer_between_own_cards() {
+        let (mut bank, first, second) = bank_with_two_users();
+        let savings = bank.open_account(first.to_string(), "0".to_string()).unwrap();
+
+        bank.transfer_between_own_accounts(first.to_string(), savings.to_string(), "40".to_string()).unwrap();
+        assert_eq!(balance(&bank, first), rub(60));
+        assert_eq!(balance(&bank, savings), rub(40));
+
+        assert_eq!(
+            bank.transfer_between_own_accounts(first.to_string(), second.to_string(), "10".to_string()).unwrap_err(),
+            BankErrors::NotOwnAccount(second)
+        );
+        assert_eq!(balance(&bank, second), rub(50));
+        assert_eq!(total_money(&bank), rub(150));
+    }
+
+    #[test]
+    fn phone_transfer_lands_on_main_card() {
+        let (mut bank, first, second) = bank_with_two_users();
+        let extra = bank.open_account(first.to_string(), "0".to_string()).unwrap();
+        bank.transfer_by_phone(second.to_string(), "+79000000111".to_string(), "5".to_string()).unwrap();
+
+        assert_eq!(balance(&bank, first), rub(105));
+        assert_eq!(balance(&bank, extra), rub(0));
+    }
+
+    #[test]
+    fn closing_cards_and_deleting_customers() {
+        let (mut bank, first, second) = bank_with_two_users();
+        let extra = bank.open_account(first.to_string(), "0".to_string()).unwrap();
+        let owner = bank.accounts[&first].check_owner();
+
+        bank.close_account(first.to_string()).unwrap();
+        assert!(!bank.accounts.contains_key(&first));
+        assert_eq!(bank.customers[&owner].check_cards(), &[extra]);
+        assert_eq!(card_of(&bank, "+79000000111"), extra);
+
+        bank.close_account(extra.to_string()).unwrap();
+        assert!(!bank.customers.contains_key(&owner));
+
+        let more = bank.open_account(second.to_string(), "0".to_string()).unwrap();
+        bank.delete_user(more.to_string()).unwrap();
+        assert!(bank.accounts.is_empty());
+        assert!(bank.customers.is_empty());
+    }
+
+    #[test]
+    fn cards_are_issued_with_configured_bin() {
+        let (mut bank, first, _) = bank_with_two_users();
+        assert!(first.digits().starts_with(DEFAULT_BIN));
+
+        assert!(bank.set_card_bin("12345".to_string()).is_err());
+        bank.set_card_bin("41234567".to_string()).unwrap();
+        let card = bank.open_account(first.to_string(), "0".to_string()).unwrap();
+        assert!(card.digits().starts_with("41234567"));
+        assert_eq!(card.to_string().parse::<CardNumber>(), Ok(card));
+    }
+
+    #[test]
+    fn phone_numbers_are_normalized_and_unique() {
+        let (mut bank, first, second) = bank_with_two_users();
+
+        assert_eq!(
+            bank.create_user("Clone C.C.".to_string(), "8 (900) 000-01-11".to_string(), "0".to_string()).unwrap_err(),
+            BankErrors::PhoneAlreadyRegistered("+79000000111".parse().unwrap())
+        );
+        assert_eq!(
+            bank.create_user("Typo T.T.".to_string(), "12-34".to_string(), "0".to_string()).unwrap_err(),
+            BankErrors::InvalidPhone("12-34".to_string())
+        );
+        assert_eq!(bank.customers.len(), 2);
+
+        bank.transfer_by_phone(second.to_string(), "8 900 000 01 11".to_string(), "5".to_string()).unwrap();
+        assert_eq!(balance(&bank, first), rub(105));
+    }
+
+    #[test]
+    fn phone_is_released_with_the_customer() {
+        let (mut bank, first, _) = bank_with_two_users();
+        bank.delete_user(first.to_string()).unwrap();
+
+        assert!(bank.find_customer_by_phone(&"+79000000111".parse().unwrap()).is_none());
+        bank.create_user("Ivanov I.I.".to_string(), "+79000000111".to_string(), "1".to_string()).unwrap();
+        assert_eq!(balance(&bank, card_of(&bank, "+79000000111")), rub(1));
+    }
+
+    #[test]
+    fn customers_are_paged_in_id_order() {
+        let (mut bank, _, _) = bank_with_two_users();
+        for i in 3..=7 {
+            bank.create_user(format!("Sidorov {i}"), format!("+7900000011{i}"), "0".to_string()).unwrap();
+        }
+        let ids = |(customers, total): (Vec<&Customer>, usize)| -> (Vec<CustomerId>, usize) {
+            (customers.iter().map(|customer| customer.get_id()).collect(), total)
+        };
+
+        assert_eq!(ids(bank.customers_page("", 0, 3)), (vec![1, 2, 3], 7));
+        assert_eq!(ids(bank.customers_page("", 2, 3)), (vec![7], 7));
+        assert_eq!(ids(bank.customers_page("sidorov", 1, 3)), (vec![6, 7], 5));
+        assert_eq!(ids(bank.customers_page("", 5, 3)), (vec![], 7));
+    }
+
+    #[test]
+    fn customers_are_found_by_name_words() {
+        let (mut bank, first, _) = bank_with_two_users();
+        bank.create_user("Петров Иван Иванович".to_string(), "+79000000333".to_string(), "0".to_string()).unwrap();
+        let names = |bank: &BankState, query: &str| -> Vec<String> {
+            bank.search_customers(query).iter().map(|customer| customer.check_fio().to_string()).collect()
+        };
+
+        assert_eq!(names(&bank, "иван петр"), vec!["Петров Иван Иванович"]);
+        assert_eq!(names(&bank, "ivan"), vec!["Ivanov I.I."]);
+        assert_eq!(names(&bank, "P"), vec!["Petrov P.P."]);
+        assert!(names(&bank, "Sidorov").is_empty());
+
+        bank.update_customer(first.to_string(), "Sidorov S.S.".to_string(), "".to_string()).unwrap();
+        assert_eq!(names(&bank, "sid"), vec!["Sidorov S.S."]);
+        assert!(names(&bank, "ivanov").is_empty());
+
+        bank.delete_user(first.to_string()).unwrap();
+        assert!(names(&bank, "sid").is_empty());
+    }
+
+    #[test]
+    fn updating_phone_keeps_index_consistent() {
+        let (mut bank, first, second) = bank_with_two_users();
+
+        assert_eq!(
+            bank.update_customer(first.to_string(), "".to_string(), "+79000000222".to_string()).unwrap_err(),
+            BankErrors::PhoneAlreadyRegistered("+79000000222".parse().unwrap())
+        );
+
+        bank.update_customer(first.to_string(), "".to_string(), "8 900 000 05 55".to_string()).unwrap();
+        assert!(bank.find_card_by_phone(&"+79000000111".parse().unwrap()).is_none());
+        assert_eq!(bank.find_card_by_phone(&"+79000000555".parse().unwrap()), Some(first));
+        assert_eq!(bank.customers[&bank.accounts[&first].check_owner()].check_fio(), "Ivanov I.I.");
+
+        bank.transfer_by_phone(second.to_string(), "+79000000555".to_string(), "1".to_string()).unwrap();
+        assert_eq!(balance(&bank, first), rub(101));
+    }
+
+    #[test]
+    fn rebuilt_indexes_match_maintained_ones() {
+        let (mut bank, first, _) = bank_with_two_users();
+        bank.update_customer(first.to_string(), "Sidorov S.S.".to_string(), "+79000000555".to_string()).unwrap();
+        let maintained = bank.index.clone();
+
+        bank.rebuild_indexes();
+        assert_eq!(format!("{:?}", bank.index.search_name("s")), format!("{:?}", maintained.search_name("s")));
+        assert_eq!(bank.index.by_phone(&"+79000000555".parse().unwrap()), maintained.by_phone(&"+79000000555".parse().unwrap()));
+        assert_eq!(bank.search_customers("sidorov").len(), 1);
+    }
+
+    #[test]
+    fn transactions_get_ids_typed_recipients_and_statuses() {
+        let (mut bank, first, second) = bank_with_two_users();
+        let by_card = bank.transfer_by_card(first.to_string(), second.to_string(), "10".to_string()).unwrap();
+        let by_phone = bank.transfer_by_phone(first.to_string(), "+79000000222".to_string(), "5".to_string()).unwrap();
+
+        //Ids 1 and 2 went to the opening deposits
+        assert_eq!((by_card.get_id(), by_phone.get_id()), (3, 4));
+        assert_eq!(by_card.get_kind(), TransactionKind::Transfer);
+        assert_eq!(by_card.get_status(), TransactionStatus::Completed);
+        assert_eq!(by_card.get_recipient(), &Recipient::Card(second));
+        assert_eq!(by_phone.get_recipient(), &Recipient::Phone("+79000000222".parse().unwrap()));
+        assert_eq!(by_phone.get_recipient_card(), Some(second));
+    }
+
+    #[test]
+    fn failed_transfers_are_recorded() {
+        let (mut bank, first, second) = bank_with_two_users();
+        bank.transfer_by_card(first.to_string(), second.to_string(), "1000".to_string()).unwrap_err();
+        bank.transfer_by_phone(first.to_string(), "+79000000333".to_string(), "1".to_string()).unwrap_err();
+        let ok = bank.transfer_by_card(first.to_string(), second.to_string(), "1".to_string()).unwrap();
+
+        let statuses: Vec<_> = bank.get_transactions().iter().skip(2).map(|tx| (tx.get_id(), tx.get_status())).collect();
+        assert_eq!(statuses, vec![
+            (3, TransactionStatus::Failed),
+            (4, TransactionStatus::Failed),
+            (5, TransactionStatus::Completed),
+        ]);
+        let unknown_phone = &bank.get_transactions()[3];
+        assert_eq!(unknown_phone.get_recipient_card(), None);
+        assert!(unknown_phone.get_credited_amount().is_zero());
+        assert_eq!(ok.get_id(), 5);
+        assert_eq!(balance(&bank, first), rub(99));
+    }
+
+    #[test]
+    fn reversal_returns_money_and_links_transactions() {
+        let (mut bank, first, second) = bank_with_two_users();
+        let tx = bank.transfer_by_card(first.to_string(), second.to_string(), "30".to_string()).unwrap();
+
+        let refund = bank.reverse_transaction(tx.get_id()).unwrap();
+        assert_eq!(balance(&bank, first), rub(100));
+        assert_eq!(balance(&bank, second), rub(50));
+        assert_eq!(refund.get_reverses(), Some(tx.get_id()));
+        assert_eq!((refund.get_sender_card(), refund.get_recipient_card()), (Some(second), Some(first)));
+
+        let original = &bank.get_transactions()[2];
+        assert_eq!(original.get_status(), TransactionStatus::Reversed);
+        assert_eq!(original.get_reversed_by(), Some(refund.get_id()));
+
+        assert_eq!(bank.reverse_transaction(tx.get_id()).unwrap_err(), BankErrors::NotReversible(tx.get_id()));
+        assert_eq!(bank.reverse_transaction(refund.get_id()).unwrap_err(), BankErrors::NotReversible(refund.get_id()));
+        assert_eq!(bank.reverse_transaction(99).unwrap_err(), BankErrors::TransactionNotFound(99));
+    }
+
+    #[test]
+    fn reversal_is_refused_when_recipient_spent_the_money() {
+        let (mut bank, first, second) = bank_with_two_users();
+        let tx = bank.transfer_by_card(first.to_string(), second.to_string(), "30".to_string()).unwrap();
+        bank.transfer_by_card(second.to_string(), first.to_string(), "70".to_string()).unwrap();
+
+        assert_eq!(
+            bank.reverse_transaction(tx.get_id()).unwrap_err(),
+            BankErrors::NotEnoughMoney { available: rub(10), requested: rub(30) }
+        );
+        assert_eq!(balance(&bank, first), rub(140));
+        assert_eq!(balance(&bank, second), rub(10));
+        assert_eq!(bank.get_transactions()[2].get_status(), TransactionStatus::Completed);
+        assert_eq!(bank.get_transactions().last().unwrap().get_status(), TransactionStatus::Failed);
+    }
+
+    #[test]
+    fn failed_transfers_can_not_be_reversed() {
+        let (mut bank, first, second) = bank_with_two_users();
+        bank.transfer_by_card(first.to_string(), second.to_string(), "1000".to_string()).unwrap_err();
+
+        let failed = bank.get_transactions().last().unwrap().get_id();
+
+        assert_eq!(bank.reverse_transaction(failed).unwrap_err(), BankErrors::NotReversible(failed));
+    }
+
+    #[test]
+    fn converted_transfer_is_reversed_at_the_original_amounts() {
+        let (mut bank, first, _) = bank_with_two_users();
+        bank.create_user("Smith J.".to_string(), "+79000000444".to_string(), "10 USD".to_string()).unwrap();
+        let dollar = card_of(&bank, "+79000000444");
+        let tx = bank.transfer_by_card(first.to_string(), dollar.to_string(), "45".to_string()).unwrap();
+        bank.set_exchange_rate("USD".to_string(), "RUB".to_string(), "120".to_string()).unwrap();
+
+        let refund = bank.reverse_transaction(tx.get_id()).unwrap();
+        assert_eq!(refund.get_amount(), Money::new(49, Currency::Usd));
+        assert_eq!(refund.get_credited_amount(), rub(45));
+        assert_eq!(balance(&bank, first), rub(100));
+        assert_eq!(balance(&bank, dollar), Money::new(1000, Currency::Usd));
+    }
+
+    #[test]
+    fn opening_balances_are_recorded_as_deposits() {
+        let (bank, first, second) = bank_with_two_users();
+        let deposits: Vec<_> = bank.get_transactions().iter()
+            .map(|tx| (tx.get_kind(), tx.get_sender_card(), tx.get_recipient_card(), tx.get_amount()))
+            .collect();
+
+        assert_eq!(deposits, vec![
+            (TransactionKind::Deposit, None, Some(first), rub(100)),
+            (TransactionKind::Deposit, None, Some(second), rub(50)),
+        ]);
+    }
+
+    #[test]
+    fn deposits_and_withdrawals_change_the_balance() {
+        let (mut bank, first, _) = bank_with_two_users();
+        let deposit = bank.deposit(first.to_string(), "25.50".to_string()).unwrap();
+        let withdrawal = bank.withdraw(first.to_string(), "0.50".to_string()).unwrap();
+
+        assert_eq!(balance(&bank, first), rub(125));
+        assert_eq!(deposit.get_kind(), TransactionKind::Deposit);
+        assert_eq!(withdrawal.get_kind(), TransactionKind::Withdrawal);
+        assert_eq!(withdrawal.get_recipient(), &Recipient::Cash);
+        assert!(!deposit.is_reversible());
+        assert!(!withdrawal.is_reversible());
+    }
+
+    #[test]
+    fn withdrawal_over_balance_is_recorded_as_failed() {
+        let (mut bank, first, _) = bank_with_two_users();
+
+        assert_eq!(
+            bank.withdraw(first.to_string(), "100.01".to_string()).unwrap_err(),
+            BankErrors::NotEnoughMoney { available: rub(100), requested: Money::new(10001, Currency::Rub) }
+        );
+        assert_eq!(bank.withdraw(first.to_string(), "0".to_string()).unwrap_err(), BankErrors::InvalidAmountProblem);
+        assert_eq!(balance(&bank, first), rub(100));
+        let last = bank.get_transactions().last().unwrap();
+        assert_eq!((last.get_kind(), last.get_status()), (TransactionKind::Withdrawal, TransactionStatus::Failed));
+    }
+
+    #[test]
+    fn deposit_in_foreign_currency_opens_that_balance() {
+        let (mut bank, first, _) = bank_with_two_users();
+        bank.deposit(first.to_string(), "5 EUR".to_string()).unwrap();
+
+        assert_eq!(balance(&bank, first), rub(100));
+        assert_eq!(bank.get_accounts()[&first].check_balance_in(Currency::Eur), Money::new(500, Currency::Eur));
+        assert_eq!(total_money(&bank), rub(150));
+    }
+
+    #[test]
+    fn books_stay_balanced_through_every_operation() {
+        let (mut bank, first, second) = bank_with_two_users();
+        bank.create_user("Smith J.".to_string(), "+79000000444".to_string(), "10 USD".to_string()).unwrap();
+        let dollar = card_of(&bank, "+79000000444");
+        let extra = bank.open_account(first.to_string(), "5".to_string()).unwrap();
+
+        bank.transfer_by_card(first.to_string(), dollar.to_string(), "45".to_string()).unwrap();
+        let tx = bank.transfer_by_phone(dollar.to_string(), "+79000000222".to_string(), "1".to_string()).unwrap();
+        bank.reverse_transaction(tx.get_id()).unwrap();
+        bank.transfer_by_card(second.to_string(), first.to_string(), "1000".to_string()).unwrap_err();
+        bank.deposit(second.to_string(), "3 EUR".to_string()).unwrap();
+        bank.withdraw(first.to_string(), "10".to_string()).unwrap();
+        bank.transfer_between_own_accounts(first.to_string(), extra.to_string(), "20".to_string()).unwrap();
+        bank.close_account(extra.to_string()).unwrap();
+        bank.delete_user(second.to_string()).unwrap();
+
+        assert_eq!(bank.check_books(), Ok(()));
+        let balances = bank.get_ledger().balances();
+        let owed: i128 = bank.accounts.values().map(|account| account.check_balance_in(Currency::Rub).minor_units() as i128).sum();
+        let outside = balances.get(&(LedgerAccount::Cash, Currency::Rub)).copied().unwrap_or(0)
+            + balances.get(&(LedgerAccount::Exchange, Currency::Rub)).copied().unwrap_or(0);
+        assert_eq!(owed + outside, 0);
+    }
+
+    #[test]
+    fn closing_a_card_pays_out_what_is_left() {
+        let (mut bank, first, _) = bank_with_two_users();
+        let extra = bank.open_account(first.to_string(), "7".to_string()).unwrap();
+        bank.close_account(extra.to_string()).unwrap();
+
+        let payout = bank.get_transactions().last().unwrap();
+        assert_eq!((payout.get_kind(), payout.get_sender_card(), payout.get_amount()), (TransactionKind::Withdrawal, Some(extra), rub(7)));
+        assert_eq!(bank.check_books(), Ok(()));
+    }
+
+    #[test]
+    fn balance_changed_behind_the_ledger_is_detected() {
+        let (mut bank, first, _) = bank_with_two_users();
+        bank.accounts.get_mut(&first).unwrap().receive_money(rub(1)).unwrap();
+
+        assert_eq!(bank.check_books(), Err(BankErrors::LedgerMismatch { card: first, currency: Currency::Rub }));
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+use std::str::FromStr;
+use rand::{Rng, thread_rng};
+use serde::{Deserialize, Serialize};
+use crate::errors::BankErrors;
+
+pub const CARD_DIGITS: usize = 16;
+
+//Issuer prefix used until the bank configures its own
+pub const DEFAULT_BIN: &str = "220099";
+
+//16-digit card number whose last digit is the Luhn check digit of the first fifteen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CardNumber(u64);
+impl CardNumber {
+    //Random number inside the bank's BIN range
+    pub fn generate(bin: &str) -> Self {
+        let mut rng = thread_rng();
+        let mut payload = bin.to_string();
+        while payload.len() < CARD_DIGITS - 1 {
+            payload.push(char::from(b'0' + rng.gen_range(0..10)));
+        }
+        payload.push(char::from(b'0' + luhn_check_digit(&payload)));
+        CardNumber(payload.parse().expect("generated card number is all digits"))
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    pub fn digits(&self) -> String {
+        format!("{:0width$}", self.0, width = CARD_DIGITS)
+    }
+
+    //Only the BIN and the last four digits are visible: "2200 99•• •••• 1234"
+    pub fn masked(&self) -> String {
+        let digits = self.digits();
+        let masked: String = digits.char_indices()
+            .map(|(i, digit)| if (6..CARD_DIGITS - 4).contains(&i) { '•' } else { digit })
+            .collect();
+        group(&masked)
+    }
+}
+impl fmt::Display for CardNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", group(&self.digits()))
+    }
+}
+impl FromStr for CardNumber {
+    type Err = BankErrors;
+
+    //Spaces and dashes between the groups are allowed: "2200 9912 3456 7890"
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || BankErrors::InvalidCardProblem(input.to_string());
+        let digits: String = input.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+        if digits.len() != CARD_DIGITS || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid())
+        }
+        let (payload, check) = digits.split_at(CARD_DIGITS - 1);
+        if check.as_bytes()[0] - b'0' != luhn_check_digit(payload) {
+            return Err(invalid())
+        }
+        digits.parse().map(CardNumber).map_err(|_| invalid())
+    }
+}
+
+//Bank identification number: the first six to eight digits of every card the bank issues
+pub fn validate_bin(bin: &str) -> Result<(), BankErrors> {
+    if !(6..=8).contains(&bin.len()) || !bin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(BankErrors::InvalidCardProblem(format!("BIN {bin}")))
+    }
+    Ok(())
+}
+
+fn luhn_check_digit(payload: &str) -> u8 {
+    let sum: u32 = payload.bytes().rev().enumerate()
+        .map(|(i, digit)| {
+            let digit = (digit - b'0') as u32;
+            match i % 2 {
+                0 if digit * 2 > 9 => digit * 2 - 9,
+                0 => digit * 2,
+                _ => digit
+            }
+        })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+fn group(digits: &str) -> String {
+    digits.chars()
+        .collect::<Vec<_>>()
+        .chunks(4)
+        .map(|chunk| chunk.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_numbers_are_valid() {
+        for _ in 0..100 {
+            let card = CardNumber::generate(DEFAULT_BIN);
+            assert!(card.digits().starts_with(DEFAULT_BIN));
+            assert_eq!(card.digits().parse::<CardNumber>(), Ok(card));
+        }
+    }
+
+    #[test]
+    fn parses_grouped_numbers_and_checks_luhn() {
+        let card: CardNumber = "4111 1111 1111 1111".parse().unwrap();
+        assert_eq!(card.to_string(), "4111 1111 1111 1111");
+        assert_eq!("4111-1111-1111-1111".parse::<CardNumber>(), Ok(card));
+
+        for input in ["4111 1111 1111 1112", "411111111111111", "41111111111111111", "4111 1111 1111 111a", ""] {
+            assert_eq!(input.parse::<CardNumber>(), Err(BankErrors::InvalidCardProblem(input.to_string())), "{input}");
+        }
+    }
+
+    #[test]
+    fn masks_middle_digits() {
+        let card: CardNumber = "4111111111111111".parse().unwrap();
+        assert_eq!(card.masked(), "4111 11•• •••• 1111");
+    }
+
+    #[test]
+    fn bin_has_six_to_eight_digits() {
+        assert!(validate_bin("220099").is_ok());
+        assert!(validate_bin("22009912").is_ok());
+        assert!(validate_bin("22009").is_err());
+        assert!(validate_bin("22OO99").is_err());
+    }
+}
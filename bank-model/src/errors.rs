@@ -0,0 +1,97 @@
+use std::fmt;
+use crate::bank::{CustomerId, TransactionId};
+use crate::card::CardNumber;
+use crate::money::{Currency, Money};
+use crate::phone::PhoneNumber;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BankErrors {
+    InvalidAmountProblem,
+    AmountOverflow,
+    UnknownCurrency(String),
+    CurrencyMismatch { expected: Currency, found: Currency },
+    NoExchangeRate { from: Currency, to: Currency },
+    InvalidExchangeRate(String),
+    InvalidCardProblem(String),
+    CantFindUserByCard(String),
+    CustomerNotFound(CustomerId),
+    SenderNotFound(CardNumber),
+    RecipientNotFound(String),
+    NotOwnAccount(CardNumber),
+    InvalidPhone(String),
+    InvalidDate(String),
+    InvalidCsvRow(String),
+    PhoneAlreadyRegistered(PhoneNumber),
+    NotEnoughMoney { available: Money, requested: Money },
+    TransactionNotFound(TransactionId),
+    NotReversible(TransactionId),
+    LedgerUnbalanced(Currency),
+    LedgerMismatch { card: CardNumber, currency: Currency },
+    StorageProblem(String),
+    JournalCorrupted(String),
+}
+
+impl BankErrors {
+    //Stable machine readable name of the error, for clients that should not parse messages
+    pub fn code(&self) -> &'static str {
+        match self {
+            BankErrors::InvalidAmountProblem => "invalid_amount",
+            BankErrors::AmountOverflow => "amount_overflow",
+            BankErrors::UnknownCurrency(_) => "unknown_currency",
+            BankErrors::CurrencyMismatch { .. } => "currency_mismatch",
+            BankErrors::NoExchangeRate { .. } => "no_exchange_rate",
+            BankErrors::InvalidExchangeRate(_) => "invalid_exchange_rate",
+            BankErrors::InvalidCardProblem(_) => "invalid_card",
+            BankErrors::CantFindUserByCard(_) => "user_not_found",
+            BankErrors::CustomerNotFound(_) => "customer_not_found",
+            BankErrors::SenderNotFound(_) => "sender_not_found",
+            BankErrors::RecipientNotFound(_) => "recipient_not_found",
+            BankErrors::NotOwnAccount(_) => "not_own_account",
+            BankErrors::InvalidPhone(_) => "invalid_phone",
+            BankErrors::InvalidDate(_) => "invalid_date",
+            BankErrors::InvalidCsvRow(_) => "invalid_csv_row",
+            BankErrors::PhoneAlreadyRegistered(_) => "phone_already_registered",
+            BankErrors::NotEnoughMoney { .. } => "not_enough_money",
+            BankErrors::TransactionNotFound(_) => "transaction_not_found",
+            BankErrors::NotReversible(_) => "not_reversible",
+            BankErrors::LedgerUnbalanced(_) => "ledger_unbalanced",
+            BankErrors::LedgerMismatch { .. } => "ledger_mismatch",
+            BankErrors::StorageProblem(_) => "storage_problem",
+            BankErrors::JournalCorrupted(_) => "journal_corrupted",
+        }
+    }
+}
+
+impl fmt::Display for BankErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BankErrors::InvalidAmountProblem => write!(f, "Invalid amount was given"),
+            BankErrors::AmountOverflow => write!(f, "Amount is too large"),
+            BankErrors::UnknownCurrency(code) => write!(f, "Unknown currency: {code}"),
+            BankErrors::CurrencyMismatch { expected, found } => write!(f, "Expected amount in {expected}, got {found}"),
+            BankErrors::NoExchangeRate { from, to } => write!(f, "No exchange rate from {from} to {to}"),
+            BankErrors::InvalidExchangeRate(rate) => write!(f, "Invalid exchange rate: {rate}"),
+            BankErrors::InvalidCardProblem(card) => write!(f, "Invalid card number: {card}"),
+            BankErrors::CantFindUserByCard(msg) => write!(f, "{msg}"),
+            BankErrors::CustomerNotFound(id) => write!(f, "Customer #{id} was not found"),
+            BankErrors::SenderNotFound(card) => write!(f, "Sender with card {card} was not found"),
+            BankErrors::RecipientNotFound(recipient) => write!(f, "Recipient {recipient} was not found"),
+            BankErrors::NotOwnAccount(card) => write!(f, "Card {card} belongs to another customer"),
+            BankErrors::InvalidPhone(phone) => write!(f, "Invalid phone number: {phone}"),
+            BankErrors::InvalidDate(date) => write!(f, "Invalid date: {date}, expected YYYY-MM-DD"),
+            BankErrors::InvalidCsvRow(msg) => write!(f, "Invalid CSV: {msg}"),
+            BankErrors::PhoneAlreadyRegistered(phone) => write!(f, "Phone number {phone} is already registered"),
+            BankErrors::NotEnoughMoney { available, requested } => {
+                write!(f, "Not enough money for transfer: {available} available, {requested} requested")
+            }
+            BankErrors::TransactionNotFound(id) => write!(f, "Transaction #{id} was not found"),
+            BankErrors::NotReversible(id) => write!(f, "Transaction #{id} can not be reversed"),
+            BankErrors::LedgerUnbalanced(currency) => write!(f, "Ledger debits and credits in {currency} differ"),
+            BankErrors::LedgerMismatch { card, currency } => {
+                write!(f, "Balance of card {card} in {currency} does not match the ledger")
+            }
+            BankErrors::StorageProblem(msg) => write!(f, "{msg}"),
+            BankErrors::JournalCorrupted(msg) => write!(f, "Journal is corrupted: {msg}"),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::errors::BankErrors;
+use crate::money::{Currency, Money};
+
+//Rates are kept as whole millionths, so conversions stay in integer arithmetic
+const RATE_DIGITS: u32 = 6;
+const RATE_SCALE: u64 = 10u64.pow(RATE_DIGITS);
+
+//How many units of `to` one unit of `from` is worth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExchangeRate {
+    pub from: Currency,
+    pub to: Currency,
+    micros: u64,
+}
+impl ExchangeRate {
+    pub fn new(from: Currency, to: Currency, micros: u64) -> Result<Self, BankErrors> {
+        if micros == 0 || from == to {
+            return Err(BankErrors::InvalidExchangeRate(format!("{from}/{to}")))
+        }
+        Ok(Self { from, to, micros })
+    }
+
+    //Accepts "92.5" style rates with up to six fractional digits
+    pub fn parse(from: Currency, to: Currency, rate: &str) -> Result<Self, BankErrors> {
+        let invalid = || BankErrors::InvalidExchangeRate(rate.trim().to_string());
+        let (whole, fraction) = rate.trim().split_once(['.', ',']).unwrap_or((rate.trim(), ""));
+        let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty() || !all_digits(whole) || !all_digits(fraction) || fraction.len() > RATE_DIGITS as usize {
+            return Err(invalid())
+        }
+
+        let whole: u64 = whole.parse().map_err(|_| invalid())?;
+        let fraction: u64 = format!("{fraction:0<width$}", width = RATE_DIGITS as usize).parse().map_err(|_| invalid())?;
+        let micros = whole.checked_mul(RATE_SCALE).and_then(|micros| micros.checked_add(fraction)).ok_or_else(invalid)?;
+        Self::new(from, to, micros)
+    }
+
+    //How many units of `to` for one `from`, "90.00" or "0.011111"
+    pub fn value(&self) -> String {
+        let whole = self.micros / RATE_SCALE;
+        let fraction = format!("{:0width$}", self.micros % RATE_SCALE, width = RATE_DIGITS as usize);
+        let fraction = fraction.trim_end_matches('0');
+        format!("{whole}.{fraction:0<2}")
+    }
+
+    //Rate of the opposite direction, rounded down
+    pub fn inverse(&self) -> Result<Self, BankErrors> {
+        Self::new(self.to, self.from, RATE_SCALE * RATE_SCALE / self.micros)
+    }
+
+    //Converted amount is rounded down to whole minor units, the bank never pays out more than it got
+    pub fn convert(&self, amount: Money) -> Result<Money, BankErrors> {
+        if amount.currency() != self.from {
+            return Err(BankErrors::CurrencyMismatch { expected: self.from, found: amount.currency() })
+        }
+        let converted = amount.minor_units() as u128 * self.micros as u128 / RATE_SCALE as u128;
+        u64::try_from(converted)
+            .map(|minor_units| Money::new(minor_units, self.to))
+            .map_err(|_| BankErrors::AmountOverflow)
+    }
+}
+impl fmt::Display for ExchangeRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "1 {} = {} {}", self.from, self.value(), self.to)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRates {
+    rates: Vec<ExchangeRate>,
+}
+impl Default for ExchangeRates {
+    fn default() -> Self {
+        let rate = |from, to, micros| ExchangeRate::new(from, to, micros).expect("default rates are valid");
+        Self {
+            rates: vec![
+                rate(Currency::Usd, Currency::Rub, 90_000_000),
+                rate(Currency::Eur, Currency::Rub, 98_000_000),
+                rate(Currency::Eur, Currency::Usd, 1_080_000),
+            ]
+        }
+    }
+}
+impl ExchangeRates {
+    pub fn get_rates(&self) -> &Vec<ExchangeRate> {
+        &self.rates
+    }
+
+    //Replaces the rate of the same direction, if there is one
+    pub fn set_rate(&mut self, rate: ExchangeRate) {
+        match self.rates.iter_mut().find(|known| known.from == rate.from && known.to == rate.to) {
+            Some(known) => *known = rate,
+            None => self.rates.push(rate)
+        }
+    }
+
+    //A configured rate for the exact direction wins, otherwise the opposite direction is inverted
+    pub fn rate(&self, from: Currency, to: Currency) -> Result<ExchangeRate, BankErrors> {
+        if let Some(rate) = self.rates.iter().find(|rate| rate.from == from && rate.to == to) {
+            return Ok(*rate)
+        }
+        match self.rates.iter().find(|rate| rate.from == to && rate.to == from) {
+            Some(rate) => rate.inverse(),
+            None => Err(BankErrors::NoExchangeRate { from, to })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_displays_rates() {
+        let rate = ExchangeRate::parse(Currency::Usd, Currency::Rub, "92.5").unwrap();
+        assert_eq!(rate.to_string(), "1 USD = 92.50 RUB");
+        assert_eq!(ExchangeRate::parse(Currency::Eur, Currency::Usd, "1.0825").unwrap().to_string(), "1 EUR = 1.0825 USD");
+        for input in ["", "0", "abc", "-1", "1.1234567"] {
+            assert!(ExchangeRate::parse(Currency::Usd, Currency::Rub, input).is_err(), "{input}");
+        }
+        assert!(ExchangeRate::parse(Currency::Usd, Currency::Usd, "1").is_err());
+    }
+
+    #[test]
+    fn converts_rounding_down() {
+        let rate = ExchangeRate::parse(Currency::Rub, Currency::Usd, "0.011").unwrap();
+        assert_eq!(rate.convert(Money::new(10_050, Currency::Rub)), Ok(Money::new(110, Currency::Usd)));
+        assert_eq!(
+            rate.convert(Money::new(100, Currency::Eur)),
+            Err(BankErrors::CurrencyMismatch { expected: Currency::Rub, found: Currency::Eur })
+        );
+    }
+
+    #[test]
+    fn missing_direction_uses_inverse_rate() {
+        let mut rates = ExchangeRates::default();
+        //1/90 is rounded down to 0.011111, and so is the converted amount
+        let to_usd = rates.rate(Currency::Rub, Currency::Usd).unwrap();
+        assert_eq!(to_usd.convert(Money::new(900_000, Currency::Rub)), Ok(Money::new(9_999, Currency::Usd)));
+
+        rates.set_rate(ExchangeRate::parse(Currency::Rub, Currency::Usd, "0.01").unwrap());
+        let to_usd = rates.rate(Currency::Rub, Currency::Usd).unwrap();
+        assert_eq!(to_usd.convert(Money::new(900_000, Currency::Rub)), Ok(Money::new(9_000, Currency::Usd)));
+        assert_eq!(rates.get_rates().len(), 4);
+    }
+}
@@ -0,0 +1,161 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use chrono::NaiveDate;
+use serde::Serialize;
+use crate::bank::{BankState, Recipient, Transaction};
+use crate::card::CardNumber;
+use crate::errors::BankErrors;
+use crate::statement::parse_date;
+
+pub const EXPORT_FILE: &str = "transactions.csv";
+
+//Which transactions go into the file: one card or the whole bank, optionally between two days (inclusive, UTC)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExportScope {
+    pub card: Option<CardNumber>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+impl ExportScope {
+    //Inputs of the reports page: an empty card means the whole bank, dates as "2024-03-31"
+    pub fn parse(card: &str, from: &str, to: &str) -> Result<Self, BankErrors> {
+        let card = match card.trim() {
+            "" => None,
+            card => Some(card.parse()?)
+        };
+        Ok(Self { card, from: parse_date(from)?, to: parse_date(to)? })
+    }
+
+    fn contains(&self, tx: &Transaction) -> bool {
+        let day = tx.get_tx_time().date_naive();
+        self.card.is_none_or(|card| tx.involves(card))
+            && self.from.is_none_or(|from| day >= from)
+            && self.to.is_none_or(|to| day <= to)
+    }
+}
+
+const CSV_HEADER: [&str; 15] = [
+    "id", "time", "kind", "status", "amount", "currency", "sender_card", "recipient_type", "recipient",
+    "recipient_card", "credited_amount", "credited_currency", "exchange_rate", "reverses", "reversed_by",
+];
+
+//One line of the file, fields in the order of `CSV_HEADER`. The column order is part of the format
+//accountants rely on: new columns may only be added at the end
+#[derive(Debug, Serialize)]
+struct CsvRow {
+    id: u64,
+    time: String,
+    kind: &'static str,
+    status: &'static str,
+    amount: String,
+    currency: &'static str,
+    sender_card: String,
+    recipient_type: &'static str,
+    recipient: String,
+    recipient_card: String,
+    credited_amount: String,
+    credited_currency: &'static str,
+    exchange_rate: String,
+    reverses: Option<u64>,
+    reversed_by: Option<u64>,
+}
+impl CsvRow {
+    fn new(tx: &Transaction) -> Self {
+        let (recipient_type, recipient) = match tx.get_recipient() {
+            Recipient::Card(card) => ("card", card.digits()),
+            Recipient::Phone(phone) => ("phone", phone.to_string()),
+            Recipient::Internal(card) => ("internal", card.digits()),
+            Recipient::Cash => ("cash", String::new()),
+        };
+
+        Self {
+            id: tx.get_id(),
+            time: tx.get_tx_time().to_rfc3339(),
+            kind: tx.get_kind().code(),
+            status: tx.get_status().code(),
+            amount: tx.get_amount().amount(),
+            currency: tx.get_amount().currency().code(),
+            sender_card: tx.get_sender_card().map(|card| card.digits()).unwrap_or_default(),
+            recipient_type,
+            recipient,
+            recipient_card: tx.get_recipient_card().map(|card| card.digits()).unwrap_or_default(),
+            credited_amount: tx.get_credited_amount().amount(),
+            credited_currency: tx.get_credited_amount().currency().code(),
+            exchange_rate: tx.get_exchange_rate().map(|rate| rate.value()).unwrap_or_default(),
+            reverses: tx.get_reverses(),
+            reversed_by: tx.get_reversed_by(),
+        }
+    }
+}
+
+//Writes the header and every transaction in scope, oldest first. Returns how many rows were written
+pub fn write_transactions<W: Write>(bank: &BankState, scope: &ExportScope, writer: W) -> Result<usize, BankErrors> {
+    let csv_problem = |e: csv::Error| BankErrors::StorageProblem(format!("Cant write CSV: {e}"));
+    //The header is written by hand so that an empty export has the same layout
+    let mut csv = csv::WriterBuilder::new().has_headers(false).from_writer(writer);
+    csv.write_record(CSV_HEADER).map_err(csv_problem)?;
+
+    let mut written = 0;
+    for tx in bank.get_transactions().iter().filter(|tx| scope.contains(tx)) {
+        csv.serialize(CsvRow::new(tx)).map_err(csv_problem)?;
+        written += 1;
+    }
+    csv.flush().map_err(|e| BankErrors::StorageProblem(format!("Cant write CSV: {e}")))?;
+    Ok(written)
+}
+
+pub fn export_transactions(bank: &BankState, scope: &ExportScope, path: &Path) -> Result<usize, BankErrors> {
+    let file = File::create(path)
+        .map_err(|e| BankErrors::StorageProblem(format!("Cant create {}: {e}", path.display())))?;
+    write_transactions(bank, scope, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{bank_with_two_users, card_of};
+
+    fn exported(bank: &BankState, scope: &ExportScope) -> (usize, Vec<String>) {
+        let mut out = Vec::new();
+        let rows = write_transactions(bank, scope, &mut out).unwrap();
+        (rows, String::from_utf8(out).unwrap().lines().map(str::to_string).collect())
+    }
+
+    fn bank_with_history() -> (BankState, CardNumber, CardNumber) {
+        let (mut bank, first, _) = bank_with_two_users();
+        bank.create_user("Smith J.".to_string(), "+79000000444".to_string(), "0 USD".to_string()).unwrap();
+        let dollar = card_of(&bank, "+79000000444");
+        bank.transfer_by_phone(first.to_string(), "+79000000444".to_string(), "90".to_string()).unwrap();
+        (bank, first, dollar)
+    }
+
+    #[test]
+    fn rows_follow_the_stable_layout() {
+        let (bank, first, dollar) = bank_with_history();
+        let (rows, lines) = exported(&bank, &ExportScope::default());
+
+        assert_eq!(rows, 3);
+        assert_eq!(lines[0], "id,time,kind,status,amount,currency,sender_card,recipient_type,recipient,recipient_card,credited_amount,credited_currency,exchange_rate,reverses,reversed_by");
+        let transfer: Vec<&str> = lines[3].split(',').collect();
+        assert_eq!(transfer.len(), CSV_HEADER.len());
+        assert_eq!(&transfer[2..], [
+            "transfer", "completed", "90.00", "RUB", &first.digits(), "phone", "+79000000444", &dollar.digits(),
+            "0.99", "USD", "0.011111", "", ""
+        ]);
+    }
+
+    #[test]
+    fn scope_limits_card_and_dates() {
+        let (bank, _, dollar) = bank_with_history();
+
+        let (rows, _) = exported(&bank, &ExportScope { card: Some(dollar), ..ExportScope::default() });
+        assert_eq!(rows, 1);
+
+        let (rows, lines) = exported(&bank, &ExportScope::parse("", "2000-01-01", "2000-12-31").unwrap());
+        assert_eq!(rows, 0);
+        assert_eq!(lines.len(), 1, "an empty export still has the header");
+
+        assert_eq!(ExportScope::parse("123", "", "").unwrap_err(), BankErrors::InvalidCardProblem("123".to_string()));
+    }
+}
@@ -0,0 +1,163 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use serde::Deserialize;
+use crate::bank::BankState;
+use crate::errors::BankErrors;
+
+//customers: fio,phone,balance
+#[derive(Debug, Deserialize)]
+struct CustomerRow {
+    fio: String,
+    phone: String,
+    #[serde(default)]
+    balance: String,
+}
+
+//transfers: sender_card,recipient_type,recipient,amount - recipient types are the ones of the export:
+//card, phone or internal (another card of the sender)
+#[derive(Debug, Deserialize)]
+struct TransferRow {
+    sender_card: String,
+    recipient_type: String,
+    recipient: String,
+    amount: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowOutcome {
+    //Line of the file, the header being line 1
+    pub line: u64,
+    pub result: Result<(), BankErrors>,
+}
+
+//Every data row is applied on its own: a bad row is reported and the import goes on
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    pub rows: Vec<RowOutcome>,
+}
+impl ImportReport {
+    pub fn imported(&self) -> usize {
+        self.rows.iter().filter(|row| row.result.is_ok()).count()
+    }
+    pub fn failed(&self) -> impl Iterator<Item = (u64, &BankErrors)> {
+        self.rows.iter().filter_map(|row| row.result.as_ref().err().map(|e| (row.line, e)))
+    }
+}
+
+pub fn import_customers<R: Read>(bank: &mut BankState, reader: R) -> Result<ImportReport, BankErrors> {
+    import_rows(reader, &["fio", "phone"], |row: CustomerRow| {
+        let balance = if row.balance.trim().is_empty() { "0".to_string() } else { row.balance };
+        bank.create_user(row.fio.trim().to_string(), row.phone, balance)
+    })
+}
+
+pub fn import_transfers<R: Read>(bank: &mut BankState, reader: R) -> Result<ImportReport, BankErrors> {
+    import_rows(reader, &["sender_card", "recipient_type", "recipient", "amount"], |row: TransferRow| {
+        let transfer = match row.recipient_type.trim().to_lowercase().as_str() {
+            "card" => bank.transfer_by_card(row.sender_card, row.recipient, row.amount),
+            "phone" => bank.transfer_by_phone(row.sender_card, row.recipient, row.amount),
+            "internal" => bank.transfer_between_own_accounts(row.sender_card, row.recipient, row.amount),
+            other => Err(BankErrors::InvalidCsvRow(format!("unknown recipient type '{other}'")))
+        };
+        transfer.map(|_| ())
+    })
+}
+
+pub fn import_file(bank: &mut BankState, path: &Path, import: fn(&mut BankState, File) -> Result<ImportReport, BankErrors>) -> Result<ImportReport, BankErrors> {
+    let file = File::open(path)
+        .map_err(|e| BankErrors::StorageProblem(format!("Cant read {}: {e}", path.display())))?;
+    import(bank, file)
+}
+
+//A missing column in the header fails the whole file, anything wrong inside a row only fails that row
+fn import_rows<R: Read, T: for<'de> Deserialize<'de>>(reader: R, columns: &[&str], mut apply: impl FnMut(T) -> Result<(), BankErrors>) -> Result<ImportReport, BankErrors> {
+    let mut csv = csv::ReaderBuilder::new().trim(csv::Trim::All).flexible(true).from_reader(reader);
+    let headers = csv.headers().map_err(|e| BankErrors::InvalidCsvRow(e.to_string()))?.clone();
+    if let Some(missing) = columns.iter().find(|column| !headers.iter().any(|header| header == **column)) {
+        return Err(BankErrors::InvalidCsvRow(format!("header has no '{missing}' column")))
+    }
+
+    let mut report = ImportReport::default();
+    for record in csv.records() {
+        let outcome = match record {
+            Ok(record) => RowOutcome {
+                line: record.position().map_or(0, |position| position.line()),
+                result: record.deserialize::<T>(Some(&headers))
+                    .map_err(|e| BankErrors::InvalidCsvRow(e.to_string()))
+                    .and_then(&mut apply)
+            },
+            Err(e) => RowOutcome {
+                line: e.position().map_or(0, |position| position.line()),
+                result: Err(BankErrors::InvalidCsvRow(e.to_string()))
+            }
+        };
+        report.rows.push(outcome);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bank::BankAccount;
+    use crate::money::{Currency, Money};
+    use crate::test_support::card_of;
+
+    #[test]
+    fn customers_are_imported_row_by_row() {
+        let mut bank = BankState::new("Import".to_string());
+        let csv = "fio,phone,balance\n\
+                   Ivanov I.I.,+79000000111,100\n\
+                   Petrov P.P., 8 900 000 02 22 ,12.50 USD\n\
+                   Broken B.B.,12345,1\n\
+                   Twin T.T.,+79000000111,5\n\
+                   Empty E.E.,+79000000333,\n\
+                   Bad B.B.,+79000000444,ten\n";
+
+        let report = import_customers(&mut bank, csv.as_bytes()).unwrap();
+        assert_eq!(report.imported(), 3);
+        assert_eq!(report.failed().collect::<Vec<_>>(), vec![
+            (4, &BankErrors::InvalidPhone("12345".to_string())),
+            (5, &BankErrors::PhoneAlreadyRegistered("+79000000111".parse().unwrap())),
+            (7, &BankErrors::InvalidAmountProblem),
+        ]);
+        assert_eq!(bank.get_accounts()[&card_of(&bank, "+79000000222")].check_balance(), Money::new(1250, Currency::Usd));
+        assert!(bank.get_accounts()[&card_of(&bank, "+79000000333")].check_balance().is_zero());
+    }
+
+    #[test]
+    fn transfers_are_imported_and_failures_reported() {
+        let mut bank = BankState::new("Import".to_string());
+        import_customers(&mut bank, "fio,phone,balance\nIvanov I.I.,+79000000111,100\nPetrov P.P.,+79000000222,0\n".as_bytes()).unwrap();
+        let (first, second) = (card_of(&bank, "+79000000111"), card_of(&bank, "+79000000222"));
+
+        let csv = format!(
+            "sender_card,recipient_type,recipient,amount\n\
+             {first},card,{second},10\n\
+             {first},phone,+79000000222,5\n\
+             {second},phone,+79000000999,1\n\
+             {first},pigeon,{second},1\n\
+             {first},card\n"
+        );
+        let report = import_transfers(&mut bank, csv.as_bytes()).unwrap();
+
+        assert_eq!(report.imported(), 2);
+        let failed: Vec<u64> = report.failed().map(|(line, _)| line).collect();
+        assert_eq!(failed, vec![4, 5, 6]);
+        assert_eq!(report.rows[2].result, Err(BankErrors::RecipientNotFound("+79000000999".to_string())));
+        assert!(matches!(report.rows[4].result, Err(BankErrors::InvalidCsvRow(_))));
+        assert_eq!(bank.get_accounts()[&second].check_balance(), Money::new(1500, Currency::Rub));
+    }
+
+    #[test]
+    fn file_without_required_columns_is_rejected() {
+        let mut bank = BankState::new("Import".to_string());
+
+        assert_eq!(
+            import_customers(&mut bank, "name,phone\nIvanov,+79000000111\n".as_bytes()).unwrap_err(),
+            BankErrors::InvalidCsvRow("header has no 'fio' column".to_string())
+        );
+        assert!(bank.get_customers().is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::bank::{BankUser, Customer, CustomerId};
+use crate::phone::PhoneNumber;
+
+//Lookups that would otherwise scan every customer: in id order, by phone and by words of the FIO.
+//Derived from the customers map, so it is never saved and is rebuilt after loading
+#[derive(Debug, Clone, Default)]
+pub struct CustomerIndex {
+    ids: BTreeSet<CustomerId>,
+    by_phone: HashMap<PhoneNumber, CustomerId>,
+    by_name: BTreeMap<String, BTreeSet<CustomerId>>,
+}
+impl CustomerIndex {
+    pub fn insert(&mut self, customer: &Customer) {
+        self.ids.insert(customer.get_id());
+        self.by_phone.insert(customer.get_phone_number().clone(), customer.get_id());
+        for word in name_words(customer.check_fio()) {
+            self.by_name.entry(word).or_default().insert(customer.get_id());
+        }
+    }
+
+    pub fn remove(&mut self, customer: &Customer) {
+        self.ids.remove(&customer.get_id());
+        self.by_phone.remove(customer.get_phone_number());
+        for word in name_words(customer.check_fio()) {
+            if let Some(ids) = self.by_name.get_mut(&word) {
+                ids.remove(&customer.get_id());
+                if ids.is_empty() {
+                    self.by_name.remove(&word);
+                }
+            }
+        }
+    }
+
+    pub fn by_phone(&self, phone: &PhoneNumber) -> Option<CustomerId> {
+        self.by_phone.get(phone).copied()
+    }
+
+    pub fn ids(&self) -> &BTreeSet<CustomerId> {
+        &self.ids
+    }
+
+    //Customers whose FIO has a word starting with every word of the query, case insensitive:
+    //"иван петр" finds "Петров Иван Иванович"
+    pub fn search_name(&self, query: &str) -> BTreeSet<CustomerId> {
+        let mut found: Option<BTreeSet<CustomerId>> = None;
+        for word in name_words(query) {
+            let matches: BTreeSet<CustomerId> = self.by_name.range(word.clone()..)
+                .take_while(|(name_word, _)| name_word.starts_with(&word))
+                .flat_map(|(_, ids)| ids.iter().copied())
+                .collect();
+            found = Some(match found {
+                Some(found) => found.intersection(&matches).copied().collect(),
+                None => matches
+            });
+        }
+        found.unwrap_or_default()
+    }
+}
+
+fn name_words(fio: &str) -> impl Iterator<Item = String> + '_ {
+    fio.split(|c: char| c.is_whitespace() || c == '.' || c == ',')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+}
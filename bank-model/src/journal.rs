@@ -0,0 +1,196 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::bank::BankOperation;
+use crate::errors::BankErrors;
+
+//Append-only write-ahead log of bank operations, kept next to the snapshot
+pub const JOURNAL_FILE: &str = "bank.journal";
+
+//Every record is one line: crc32 of the payload in hex, a space and the JSON payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalRecord {
+    pub seq: u64,
+    pub op: BankOperation,
+}
+
+#[derive(Debug, Clone)]
+pub struct Journal {
+    path: PathBuf,
+    records: usize,
+}
+impl Journal {
+    //Opens the journal and returns every intact record in it.
+    //A torn final record (crash in the middle of a write) is cut off the file,
+    //a broken record anywhere else means the journal can't be trusted and is an error
+    pub fn open(path: &Path) -> Result<(Self, Vec<JournalRecord>), BankErrors> {
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(io_problem(path, e))
+        };
+
+        let mut records = Vec::new();
+        let mut valid_len = 0;
+        let mut line_start = 0;
+
+        while line_start < content.len() {
+            let line_end = content[line_start..].iter().position(|&b| b == b'\n').map(|pos| line_start + pos);
+            let record = line_end.and_then(|end| decode_record(&content[line_start..end]));
+
+            match (record, line_end) {
+                (Some(record), Some(end)) => {
+                    records.push(record);
+                    line_start = end + 1;
+                    valid_len = line_start;
+                }
+                (_, Some(end)) if end + 1 < content.len() => {
+                    return Err(BankErrors::JournalCorrupted(format!("record {} of {} is damaged", records.len() + 1, path.display())))
+                }
+                _ => break
+            }
+        }
+
+        if valid_len < content.len() {
+            let file = OpenOptions::new().write(true).open(path).map_err(|e| io_problem(path, e))?;
+            file.set_len(valid_len as u64).and_then(|_| file.sync_all()).map_err(|e| io_problem(path, e))?;
+        }
+
+        let journal = Self { path: path.to_path_buf(), records: records.len() };
+        Ok((journal, records))
+    }
+
+    pub fn append(&mut self, seq: u64, op: &BankOperation) -> Result<(), BankErrors> {
+        let record = JournalRecord { seq, op: op.clone() };
+        let payload = serde_json::to_string(&record)
+            .map_err(|e| BankErrors::StorageProblem(format!("Cant serialize journal record: {e}")))?;
+        let line = format!("{:08x} {payload}\n", crc32fast::hash(payload.as_bytes()));
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)
+            .map_err(|e| io_problem(&self.path, e))?;
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .map_err(|e| io_problem(&self.path, e))?;
+
+        self.records += 1;
+        Ok(())
+    }
+
+    //Drops every record, used once they are folded into a snapshot
+    pub fn truncate(&mut self) -> Result<(), BankErrors> {
+        File::create(&self.path)
+            .and_then(|file| file.sync_all())
+            .map_err(|e| io_problem(&self.path, e))?;
+        self.records = 0;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.records
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records == 0
+    }
+}
+
+fn decode_record(line: &[u8]) -> Option<JournalRecord> {
+    let line = std::str::from_utf8(line).ok()?;
+    let (checksum, payload) = line.split_once(' ')?;
+    let checksum = u32::from_str_radix(checksum, 16).ok()?;
+    if crc32fast::hash(payload.as_bytes()) != checksum {
+        return None
+    }
+    serde_json::from_str(payload).ok()
+}
+
+fn io_problem(path: &Path, e: std::io::Error) -> BankErrors {
+    BankErrors::StorageProblem(format!("Cant access {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_journal(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{name}-{}.journal", std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn bin_op(bin: &str) -> BankOperation {
+        BankOperation::SetCardBin { bin: bin.to_string() }
+    }
+
+    #[test]
+    fn appended_records_are_read_back() {
+        let path = temp_journal("journal-roundtrip");
+        let (mut journal, records) = Journal::open(&path).unwrap();
+        assert!(records.is_empty());
+
+        journal.append(1, &bin_op("220010")).unwrap();
+        journal.append(2, &bin_op("220020")).unwrap();
+
+        let (journal, records) = Journal::open(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(journal.len(), 2);
+        assert_eq!(records, vec![
+            JournalRecord { seq: 1, op: bin_op("220010") },
+            JournalRecord { seq: 2, op: bin_op("220020") },
+        ]);
+    }
+
+    #[test]
+    fn torn_final_record_is_discarded() {
+        let path = temp_journal("journal-torn");
+        let (mut journal, _) = Journal::open(&path).unwrap();
+        journal.append(1, &bin_op("220010")).unwrap();
+        let intact_len = fs::metadata(&path).unwrap().len();
+        journal.append(2, &bin_op("220020")).unwrap();
+
+        //Simulate a crash in the middle of the second write
+        let content = fs::read(&path).unwrap();
+        fs::write(&path, &content[..content.len() - 5]).unwrap();
+
+        let (_, records) = Journal::open(&path).unwrap();
+        let repaired_len = fs::metadata(&path).unwrap().len();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(records, vec![JournalRecord { seq: 1, op: bin_op("220010") }]);
+        assert_eq!(repaired_len, intact_len);
+    }
+
+    #[test]
+    fn final_record_with_bad_checksum_is_discarded() {
+        let path = temp_journal("journal-checksum");
+        let (mut journal, _) = Journal::open(&path).unwrap();
+        journal.append(1, &bin_op("220010")).unwrap();
+        journal.append(2, &bin_op("220020")).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap().replace("220020", "220021");
+        fs::write(&path, content).unwrap();
+
+        let (_, records) = Journal::open(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(records, vec![JournalRecord { seq: 1, op: bin_op("220010") }]);
+    }
+
+    #[test]
+    fn damaged_record_in_the_middle_is_an_error() {
+        let path = temp_journal("journal-damaged");
+        let (mut journal, _) = Journal::open(&path).unwrap();
+        journal.append(1, &bin_op("220010")).unwrap();
+        journal.append(2, &bin_op("220020")).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap().replace("220010", "220011");
+        fs::write(&path, content).unwrap();
+
+        let result = Journal::open(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(BankErrors::JournalCorrupted(_))));
+    }
+}
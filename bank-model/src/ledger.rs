@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::bank::{Transaction, TransactionId};
+use crate::card::CardNumber;
+use crate::money::{Currency, Money};
+
+//Accounts of the bank's books. A card is what the bank owes to its holder, cash is money that came in
+//from or went out to the outside world, exchange is the bank's own position from converting currencies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LedgerAccount {
+    Card(CardNumber),
+    Cash,
+    Exchange,
+}
+
+//Postings of balances that were there before the ledger, transaction ids start from 1
+pub const OPENING_ENTRY: TransactionId = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Debit,
+    Credit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Posting {
+    pub transaction_id: TransactionId,
+    pub account: LedgerAccount,
+    pub side: Side,
+    pub amount: Money,
+}
+
+//Sum of all debits and credits in one currency, equal when the books balance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub debits: u128,
+    pub credits: u128,
+}
+impl Totals {
+    pub fn is_balanced(&self) -> bool {
+        self.debits == self.credits
+    }
+}
+
+//Append-only double-entry journal of every completed money movement
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ledger {
+    postings: Vec<Posting>,
+}
+impl Ledger {
+    pub fn get_postings(&self) -> &[Posting] {
+        &self.postings
+    }
+
+    //The payer is debited and the payee credited; deposits come from cash and withdrawals go to it.
+    //A conversion passes through the exchange account, so every currency balances on its own
+    pub fn post_transaction(&mut self, tx: &Transaction) {
+        let payer = tx.get_sender_card().map_or(LedgerAccount::Cash, LedgerAccount::Card);
+        let payee = tx.get_recipient_card().map_or(LedgerAccount::Cash, LedgerAccount::Card);
+        let (amount, credited) = (tx.get_amount(), tx.get_credited_amount());
+        let posting = |account, side, amount| Posting { transaction_id: tx.get_id(), account, side, amount };
+
+        self.postings.push(posting(payer, Side::Debit, amount));
+        if amount.currency() != credited.currency() {
+            self.postings.push(posting(LedgerAccount::Exchange, Side::Credit, amount));
+            self.postings.push(posting(LedgerAccount::Exchange, Side::Debit, credited));
+        }
+        self.postings.push(posting(payee, Side::Credit, credited));
+    }
+
+    //Brings a balance the books have never seen in as a deposit from cash
+    pub fn post_opening_balance(&mut self, card: CardNumber, amount: Money) {
+        let posting = |account, side| Posting { transaction_id: OPENING_ENTRY, account, side, amount };
+        self.postings.push(posting(LedgerAccount::Cash, Side::Debit));
+        self.postings.push(posting(LedgerAccount::Card(card), Side::Credit));
+    }
+
+    //Credits minus debits in minor units: positive for cards, the other way round for cash and exchange
+    pub fn balances(&self) -> BTreeMap<(LedgerAccount, Currency), i128> {
+        let mut balances = BTreeMap::new();
+        for posting in &self.postings {
+            let units = posting.amount.minor_units() as i128;
+            *balances.entry((posting.account, posting.amount.currency())).or_insert(0) += match posting.side {
+                Side::Debit => -units,
+                Side::Credit => units,
+            };
+        }
+        balances
+    }
+
+    pub fn trial_balance(&self) -> BTreeMap<Currency, Totals> {
+        let mut totals: BTreeMap<Currency, Totals> = BTreeMap::new();
+        for posting in &self.postings {
+            let line = totals.entry(posting.amount.currency()).or_default();
+            match posting.side {
+                Side::Debit => line.debits += posting.amount.minor_units() as u128,
+                Side::Credit => line.credits += posting.amount.minor_units() as u128,
+            }
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{bank_with_two_users, card_of, rub};
+
+    #[test]
+    fn deposit_and_transfer_post_two_sided_entries() {
+        let (mut bank, first, second) = bank_with_two_users();
+        bank.transfer_by_card(first.to_string(), second.to_string(), "40".to_string()).unwrap();
+
+        let entries: Vec<_> = bank.get_ledger().get_postings().iter()
+            .map(|posting| (posting.transaction_id, posting.account, posting.side, posting.amount))
+            .collect();
+        assert_eq!(entries, vec![
+            (1, LedgerAccount::Cash, Side::Debit, rub(100)),
+            (1, LedgerAccount::Card(first), Side::Credit, rub(100)),
+            (2, LedgerAccount::Cash, Side::Debit, rub(50)),
+            (2, LedgerAccount::Card(second), Side::Credit, rub(50)),
+            (3, LedgerAccount::Card(first), Side::Debit, rub(40)),
+            (3, LedgerAccount::Card(second), Side::Credit, rub(40)),
+        ]);
+        assert_eq!(bank.get_ledger().trial_balance()[&Currency::Rub], Totals { debits: 19000, credits: 19000 });
+    }
+
+    #[test]
+    fn conversion_goes_through_the_exchange_account() {
+        let (mut bank, rub_card, _) = bank_with_two_users();
+        bank.create_user("Smith J.".to_string(), "+79000000444".to_string(), "0 USD".to_string()).unwrap();
+        let usd_card = card_of(&bank, "+79000000444");
+        bank.transfer_by_card(rub_card.to_string(), usd_card.to_string(), "90".to_string()).unwrap();
+
+        let balances = bank.get_ledger().balances();
+        assert_eq!(balances[&(LedgerAccount::Exchange, Currency::Rub)], 9000);
+        //90 RUB at the inverted 1 USD = 90 RUB rate is rounded down to 0.99 USD
+        assert_eq!(balances[&(LedgerAccount::Exchange, Currency::Usd)], -99);
+        assert_eq!(balances[&(LedgerAccount::Card(usd_card), Currency::Usd)], 99);
+        assert!(bank.get_ledger().trial_balance().values().all(Totals::is_balanced));
+    }
+}
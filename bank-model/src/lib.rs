@@ -0,0 +1,27 @@
+//Everything about the bank itself: customers, cards, money, transactions and how they are stored.
+//No UI here, the GUI and the CLI are both just consumers of this crate
+pub mod bank;
+pub mod card;
+pub mod errors;
+pub mod exchange;
+pub mod export;
+pub mod import;
+mod index;
+pub mod journal;
+pub mod ledger;
+pub mod money;
+pub mod phone;
+pub mod shared;
+pub mod statement;
+pub mod storage;
+#[cfg(test)]
+mod test_support;
+
+pub use crate::bank::{
+    Account, BankAccount, BankOperation, BankState, BankUser, Customer, CustomerId, Recipient, Transaction,
+    TransactionId, TransactionKind, TransactionStatus,
+};
+//Customers used to be called users, and still are on the GUI side
+pub use crate::bank::Customer as User;
+pub use crate::errors::BankErrors;
+pub use crate::shared::SharedBank;
@@ -0,0 +1,171 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::errors::BankErrors;
+
+//Every supported currency has two minor digits (kopecks, cents)
+const MINOR_DIGITS: u32 = 2;
+const MINOR_IN_MAJOR: u64 = 10u64.pow(MINOR_DIGITS);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub enum Currency {
+    #[default]
+    Rub,
+    Usd,
+    Eur,
+}
+impl Currency {
+    pub const ALL: [Currency; 3] = [Currency::Rub, Currency::Usd, Currency::Eur];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Rub => "RUB",
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+        }
+    }
+}
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+impl FromStr for Currency {
+    type Err = BankErrors;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Currency::ALL.into_iter()
+            .find(|currency| currency.code().eq_ignore_ascii_case(code.trim()))
+            .ok_or_else(|| BankErrors::UnknownCurrency(code.trim().to_string()))
+    }
+}
+
+//Exact amount of money: whole number of minor units in a particular currency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    minor_units: u64,
+    currency: Currency,
+}
+impl Money {
+    pub fn new(minor_units: u64, currency: Currency) -> Self {
+        Self { minor_units, currency }
+    }
+    pub fn minor_units(&self) -> u64 {
+        self.minor_units
+    }
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+    pub fn is_zero(&self) -> bool {
+        self.minor_units == 0
+    }
+    //Just the number, "12.50", without the currency code
+    pub fn amount(&self) -> String {
+        let major = self.minor_units / MINOR_IN_MAJOR;
+        let minor = self.minor_units % MINOR_IN_MAJOR;
+        format!("{major}.{minor:0width$}", width = MINOR_DIGITS as usize)
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, BankErrors> {
+        self.same_currency(other)?;
+        self.minor_units.checked_add(other.minor_units)
+            .map(|minor_units| Money::new(minor_units, self.currency))
+            .ok_or(BankErrors::AmountOverflow)
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, BankErrors> {
+        self.same_currency(other)?;
+        self.minor_units.checked_sub(other.minor_units)
+            .map(|minor_units| Money::new(minor_units, self.currency))
+            .ok_or(BankErrors::NotEnoughMoney { available: self, requested: other })
+    }
+
+    fn same_currency(&self, other: Money) -> Result<(), BankErrors> {
+        if self.currency != other.currency {
+            return Err(BankErrors::CurrencyMismatch { expected: self.currency, found: other.currency })
+        }
+        Ok(())
+    }
+
+    //Accepts "12", "12.5", "12.50" or "12,50", optionally followed by a currency code: "12.50 USD"
+    pub fn parse(input: &str, default_currency: Currency) -> Result<Money, BankErrors> {
+        let mut parts = input.split_whitespace();
+        let amount = parts.next().ok_or(BankErrors::InvalidAmountProblem)?;
+        let currency = match parts.next() {
+            Some(code) => code.parse()?,
+            None => default_currency
+        };
+        if parts.next().is_some() {
+            return Err(BankErrors::InvalidAmountProblem)
+        }
+
+        let (major, minor) = match amount.split_once(['.', ',']) {
+            Some((major, minor)) => (major, minor),
+            None => (amount, "")
+        };
+        let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if major.is_empty() || !all_digits(major) || !all_digits(minor) || minor.len() > MINOR_DIGITS as usize {
+            return Err(BankErrors::InvalidAmountProblem)
+        }
+
+        let major: u64 = major.parse().map_err(|_| BankErrors::AmountOverflow)?;
+        let minor: u64 = format!("{minor:0<width$}", width = MINOR_DIGITS as usize).parse()
+            .map_err(|_| BankErrors::InvalidAmountProblem)?;
+
+        major.checked_mul(MINOR_IN_MAJOR)
+            .and_then(|units| units.checked_add(minor))
+            .map(|minor_units| Money::new(minor_units, currency))
+            .ok_or(BankErrors::AmountOverflow)
+    }
+}
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount(), self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rub(minor_units: u64) -> Money {
+        Money::new(minor_units, Currency::Rub)
+    }
+
+    #[test]
+    fn parses_major_and_minor_units() {
+        assert_eq!(Money::parse("12.50", Currency::Rub), Ok(rub(1250)));
+        assert_eq!(Money::parse("12,5", Currency::Rub), Ok(rub(1250)));
+        assert_eq!(Money::parse("12", Currency::Rub), Ok(rub(1200)));
+        assert_eq!(Money::parse(" 0.07 ", Currency::Rub), Ok(rub(7)));
+        assert_eq!(Money::parse("3.10 usd", Currency::Rub), Ok(Money::new(310, Currency::Usd)));
+    }
+
+    #[test]
+    fn rejects_malformed_amounts() {
+        for input in ["", "abc", "-5", "1.234", ".5", "1.2.3", "12 RUB extra"] {
+            assert_eq!(Money::parse(input, Currency::Rub), Err(BankErrors::InvalidAmountProblem), "{input}");
+        }
+        assert_eq!(Money::parse("5 GBP", Currency::Rub), Err(BankErrors::UnknownCurrency("GBP".to_string())));
+        assert_eq!(Money::parse("99999999999999999999", Currency::Rub), Err(BankErrors::AmountOverflow));
+        assert_eq!(Money::parse("184467440737095516.16", Currency::Rub), Err(BankErrors::AmountOverflow));
+    }
+
+    #[test]
+    fn arithmetic_is_checked() {
+        assert_eq!(rub(100).checked_add(rub(50)), Ok(rub(150)));
+        assert_eq!(rub(u64::MAX).checked_add(rub(1)), Err(BankErrors::AmountOverflow));
+        assert_eq!(rub(100).checked_sub(rub(30)), Ok(rub(70)));
+        assert_eq!(rub(10).checked_sub(rub(30)), Err(BankErrors::NotEnoughMoney { available: rub(10), requested: rub(30) }));
+        assert_eq!(
+            rub(10).checked_add(Money::new(1, Currency::Usd)),
+            Err(BankErrors::CurrencyMismatch { expected: Currency::Rub, found: Currency::Usd })
+        );
+    }
+
+    #[test]
+    fn displays_with_minor_digits_and_code() {
+        assert_eq!(rub(1250).to_string(), "12.50 RUB");
+        assert_eq!(Money::new(7, Currency::Eur).to_string(), "0.07 EUR");
+    }
+}
@@ -0,0 +1,85 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::errors::BankErrors;
+
+//Phone number in E.164 form: "+" followed by up to fifteen digits, e.g. "+79123456789"
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PhoneNumber(String);
+impl PhoneNumber {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    //Digits without the leading "+"
+    pub fn digits(&self) -> &str {
+        &self.0[1..]
+    }
+}
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl FromStr for PhoneNumber {
+    type Err = BankErrors;
+
+    //Numbers without a country code are taken as Russian: "8 (912) 345-67-89" and "912 345 67 89"
+    //both become "+79123456789"
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || BankErrors::InvalidPhone(input.trim().to_string());
+        let cleaned: String = input.chars()
+            .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '(' | ')' | '.'))
+            .collect();
+        let (international, digits) = match cleaned.strip_prefix('+') {
+            Some(digits) => (true, digits),
+            None => (false, cleaned.as_str())
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid())
+        }
+
+        let normalized = match (international, digits.len(), digits.as_bytes()[0]) {
+            (true, 8..=15, first) if first != b'0' => digits.to_string(),
+            (false, 11, b'8') => format!("7{}", &digits[1..]),
+            (false, 11, b'7') => digits.to_string(),
+            (false, 10, _) => format!("7{digits}"),
+            _ => return Err(invalid())
+        };
+        Ok(PhoneNumber(format!("+{normalized}")))
+    }
+}
+impl TryFrom<String> for PhoneNumber {
+    type Error = BankErrors;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+impl From<PhoneNumber> for String {
+    fn from(phone: PhoneNumber) -> Self {
+        phone.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_to_e164() {
+        for input in ["+7 912 345-67-89", "8 (912) 345-67-89", "79123456789", "9123456789", "+7(912)3456789"] {
+            assert_eq!(input.parse::<PhoneNumber>().unwrap().as_str(), "+79123456789", "{input}");
+        }
+        assert_eq!("+1 (555) 010-9999".parse::<PhoneNumber>().unwrap().as_str(), "+15550109999");
+        assert_eq!("+49 30 123456".parse::<PhoneNumber>().unwrap().digits(), "4930123456");
+    }
+
+    #[test]
+    fn rejects_malformed_numbers() {
+        for input in ["", "111", "+", "abc", "+7912345678901234", "+0123456789", "6123456789012", "+7 912 ext 5"] {
+            assert_eq!(input.parse::<PhoneNumber>(), Err(BankErrors::InvalidPhone(input.trim().to_string())), "{input}");
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::{Arc, RwLock};
+use crate::bank::BankState;
+
+//Handle to one bank for many threads: readers share it, every operation gets it exclusively.
+//One lock for the whole bank means a transfer between any two cards can neither interleave with
+//another one touching the same cards nor deadlock on them, and the journal keeps the same order as the state
+#[derive(Debug, Clone)]
+pub struct SharedBank {
+    bank: Arc<RwLock<BankState>>,
+}
+impl SharedBank {
+    pub fn new(bank: BankState) -> Self {
+        Self { bank: Arc::new(RwLock::new(bank)) }
+    }
+
+    pub fn read<R>(&self, f: impl FnOnce(&BankState) -> R) -> R {
+        f(&self.bank.read().expect("bank lock poisoned by a panicked operation"))
+    }
+
+    pub fn write<R>(&self, f: impl FnOnce(&mut BankState) -> R) -> R {
+        f(&mut self.bank.write().expect("bank lock poisoned by a panicked operation"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use crate::bank::BankUser;
+    use crate::card::CardNumber;
+    use crate::test_support::{bank_with_two_users, total_money};
+
+    const CUSTOMERS: usize = 10;
+    const THREADS: usize = 8;
+    const TRANSFERS_PER_THREAD: usize = 500;
+
+    #[test]
+    fn parallel_transfers_conserve_money() {
+        let mut bank = BankState::new("Test".to_string());
+        for i in 0..CUSTOMERS {
+            bank.create_user(format!("Customer {i}"), format!("+7900000{i:04}"), "100".to_string()).unwrap();
+        }
+        let cards: Vec<CardNumber> = (0..CUSTOMERS)
+            .map(|i| bank.find_customer_by_phone(&format!("+7900000{i:04}").parse().unwrap()).unwrap().check_main_card())
+            .collect();
+        let before = total_money(&bank);
+        let transactions_before = bank.get_transactions().len();
+        let shared = SharedBank::new(bank);
+
+        //Every thread walks the cards in its own order, so the same pairs are hit from both sides at once
+        let workers: Vec<_> = (0..THREADS).map(|t| {
+            let shared = shared.clone();
+            let cards = cards.clone();
+            thread::spawn(move || {
+                for i in 0..TRANSFERS_PER_THREAD {
+                    let from = cards[(t + i) % CUSTOMERS];
+                    let to = cards[(t * 7 + i * 3 + 1) % CUSTOMERS];
+                    let amount = format!("{}.{:02}", i % 40, (t * 13 + i) % 100);
+                    //Not enough money and transfers to the same card are fine here, only the totals matter
+                    let _ = shared.write(|bank| bank.transfer_by_card(from.digits(), to.digits(), amount));
+                }
+            })
+        }).collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        shared.read(|bank| {
+            assert_eq!(total_money(bank), before);
+            assert_eq!(bank.check_books(), Ok(()));
+            //Every attempt got past validation is recorded, completed or failed
+            assert!(bank.get_transactions().len() > transactions_before + THREADS * TRANSFERS_PER_THREAD / 2);
+        });
+    }
+
+    #[test]
+    fn readers_see_whole_operations() {
+        let (bank, _, _) = bank_with_two_users();
+        let shared = SharedBank::new(bank);
+        let before = shared.read(total_money);
+
+        let writer = {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    let _ = shared.write(|bank| bank.transfer_by_phone(
+                        bank.find_card_by_phone(&"+79000000111".parse().unwrap()).unwrap().digits(),
+                        "+79000000222".to_string(),
+                        "0.01".to_string()
+                    ));
+                }
+            })
+        };
+        for _ in 0..1000 {
+            assert_eq!(shared.read(total_money), before);
+        }
+        writer.join().unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use chrono::NaiveDate;
+use crate::bank::{BankState, Transaction, TransactionStatus};
+use crate::card::CardNumber;
+use crate::errors::BankErrors;
+use crate::money::{Currency, Money};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Incoming,
+    Outgoing,
+}
+
+//Empty bounds let everything through. Dates are inclusive days in UTC,
+//amount bounds are in one currency and lines in other currencies never match them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatementFilter {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub direction: Option<Direction>,
+    pub min_amount: Option<Money>,
+    pub max_amount: Option<Money>,
+}
+impl StatementFilter {
+    //Builds a filter from the statement page inputs: dates as "2024-03-31", amounts as in transfers
+    pub fn parse(from: &str, to: &str, direction: Option<Direction>, min_amount: &str, max_amount: &str, currency: Currency) -> Result<Self, BankErrors> {
+        let amount = |input: &str| match input.trim() {
+            "" => Ok(None),
+            amount => Money::parse(amount, currency).map(Some)
+        };
+
+        Ok(Self {
+            from: parse_date(from)?,
+            to: parse_date(to)?,
+            direction,
+            min_amount: amount(min_amount)?,
+            max_amount: amount(max_amount)?,
+        })
+    }
+
+    fn matches(&self, line: &StatementLine) -> bool {
+        let day = line.tx.get_tx_time().date_naive();
+        let amount_within = |bound: Option<Money>, within: fn(u64, u64) -> bool| match bound {
+            Some(bound) => bound.currency() == line.amount.currency() && within(line.amount.minor_units(), bound.minor_units()),
+            None => true
+        };
+
+        self.from.is_none_or(|from| day >= from)
+            && self.to.is_none_or(|to| day <= to)
+            && self.direction.is_none_or(|direction| direction == line.direction)
+            && amount_within(self.min_amount, |amount, min| amount >= min)
+            && amount_within(self.max_amount, |amount, max| amount <= max)
+    }
+}
+
+//Optional "2024-03-31" style date, an empty input means no bound
+pub fn parse_date(input: &str) -> Result<Option<NaiveDate>, BankErrors> {
+    match input.trim() {
+        "" => Ok(None),
+        date => NaiveDate::parse_from_str(date, "%Y-%m-%d").map(Some).map_err(|_| BankErrors::InvalidDate(date.to_string()))
+    }
+}
+
+//One movement of money on the card, with the card's balance in that currency right after it
+#[derive(Debug, Clone, Copy)]
+pub struct StatementLine<'a> {
+    pub tx: &'a Transaction,
+    pub direction: Direction,
+    pub amount: Money,
+    pub balance_after: Money,
+}
+
+//Executed transactions of the card, oldest first. The running balance is counted over the whole
+//history and only then filtered, so it always shows what the card really held at that moment
+pub fn statement<'a>(bank: &'a BankState, card: CardNumber, filter: &StatementFilter) -> Vec<StatementLine<'a>> {
+    let mut balances: BTreeMap<Currency, u64> = BTreeMap::new();
+    let mut lines = Vec::new();
+
+    let executed = bank.get_transactions().iter()
+        .filter(|tx| matches!(tx.get_status(), TransactionStatus::Completed | TransactionStatus::Reversed));
+    for tx in executed {
+        let mut movements = Vec::new();
+        if tx.get_sender_card() == Some(card) {
+            movements.push((Direction::Outgoing, tx.get_amount()));
+        }
+        if tx.get_recipient_card() == Some(card) {
+            movements.push((Direction::Incoming, tx.get_credited_amount()));
+        }
+
+        for (direction, amount) in movements {
+            let balance = balances.entry(amount.currency()).or_insert(0);
+            *balance = match direction {
+                Direction::Incoming => balance.saturating_add(amount.minor_units()),
+                Direction::Outgoing => balance.saturating_sub(amount.minor_units()),
+            };
+            let line = StatementLine { tx, direction, amount, balance_after: Money::new(*balance, amount.currency()) };
+            if filter.matches(&line) {
+                lines.push(line);
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Days, Utc};
+    use crate::test_support::{bank_with_two_users, rub};
+
+    //First card: +100 opening, -30 to the second, +5 back, a failed -1000, -10 cash
+    fn bank_with_history() -> (BankState, CardNumber) {
+        let (mut bank, first, second) = bank_with_two_users();
+        bank.transfer_by_card(first.to_string(), second.to_string(), "30".to_string()).unwrap();
+        bank.transfer_by_phone(second.to_string(), "+79000000111".to_string(), "5".to_string()).unwrap();
+        bank.transfer_by_card(first.to_string(), second.to_string(), "1000".to_string()).unwrap_err();
+        bank.withdraw(first.to_string(), "10".to_string()).unwrap();
+        (bank, first)
+    }
+
+    fn amounts(lines: &[StatementLine]) -> Vec<(Direction, Money, Money)> {
+        lines.iter().map(|line| (line.direction, line.amount, line.balance_after)).collect()
+    }
+
+    #[test]
+    fn running_balance_follows_executed_transactions() {
+        let (bank, first) = bank_with_history();
+        let lines = statement(&bank, first, &StatementFilter::default());
+
+        assert_eq!(amounts(&lines), vec![
+            (Direction::Incoming, rub(100), rub(100)),
+            (Direction::Outgoing, rub(30), rub(70)),
+            (Direction::Incoming, rub(5), rub(75)),
+            (Direction::Outgoing, rub(10), rub(65)),
+        ]);
+    }
+
+    #[test]
+    fn filters_keep_the_real_running_balance() {
+        let (bank, first) = bank_with_history();
+        let outgoing = StatementFilter { direction: Some(Direction::Outgoing), ..StatementFilter::default() };
+        assert_eq!(amounts(&statement(&bank, first, &outgoing)), vec![
+            (Direction::Outgoing, rub(30), rub(70)),
+            (Direction::Outgoing, rub(10), rub(65)),
+        ]);
+
+        let mid_sized = StatementFilter::parse("", "", None, "6", "50", Currency::Rub).unwrap();
+        assert_eq!(amounts(&statement(&bank, first, &mid_sized)), vec![
+            (Direction::Outgoing, rub(30), rub(70)),
+            (Direction::Outgoing, rub(10), rub(65)),
+        ]);
+
+        let in_dollars = StatementFilter::parse("", "", None, "1", "", Currency::Usd).unwrap();
+        assert!(statement(&bank, first, &in_dollars).is_empty());
+    }
+
+    #[test]
+    fn date_bounds_are_inclusive_days() {
+        let (bank, first) = bank_with_history();
+        let today = Utc::now().date_naive();
+        let tomorrow = today.checked_add_days(Days::new(1)).unwrap();
+
+        let only_today = StatementFilter { from: Some(today), to: Some(today), ..StatementFilter::default() };
+        assert_eq!(statement(&bank, first, &only_today).len(), 4);
+        let from_tomorrow = StatementFilter { from: Some(tomorrow), ..StatementFilter::default() };
+        assert!(statement(&bank, first, &from_tomorrow).is_empty());
+    }
+
+    #[test]
+    fn malformed_filter_inputs_are_rejected() {
+        assert_eq!(
+            StatementFilter::parse("31.12.2024", "", None, "", "", Currency::Rub).unwrap_err(),
+            BankErrors::InvalidDate("31.12.2024".to_string())
+        );
+        assert_eq!(
+            StatementFilter::parse("", "", None, "abc", "", Currency::Rub).unwrap_err(),
+            BankErrors::InvalidAmountProblem
+        );
+        let parsed = StatementFilter::parse("2024-01-01", " 2024-12-31 ", None, "", "", Currency::Rub).unwrap();
+        assert_eq!(parsed.from, NaiveDate::from_ymd_opt(2024, 1, 1));
+        assert_eq!(parsed.to, NaiveDate::from_ymd_opt(2024, 12, 31));
+    }
+}
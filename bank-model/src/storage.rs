@@ -0,0 +1,274 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::bank::BankState;
+use crate::errors::BankErrors;
+use crate::journal::Journal;
+
+//Snapshot of the whole bank, written next to the executable's working directory
+pub const BANK_FILE: &str = "bank.json";
+
+//Once the journal grows past this many records it is folded into a fresh snapshot
+pub const COMPACT_AFTER: usize = 100;
+
+//Only one process may work with the bank files at a time. The lock lives next to the snapshot
+//while the bank is open and is removed when the bank is dropped
+#[derive(Debug)]
+pub struct BankLock {
+    path: PathBuf,
+}
+impl BankLock {
+    fn take(snapshot_path: &Path) -> Result<Self, BankErrors> {
+        let path = snapshot_path.with_extension("lock");
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                //Only to tell who holds it, the lock itself is the file being there
+                let _ = writeln!(file, "{}", std::process::id());
+                Ok(Self { path })
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => Err(BankErrors::StorageProblem(format!(
+                "{} is used by another process, remove {} if that process is no longer running",
+                snapshot_path.display(), path.display()
+            ))),
+            Err(e) => Err(BankErrors::StorageProblem(format!("Cant create {}: {e}", path.display())))
+        }
+    }
+}
+impl Drop for BankLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+pub fn save_bank(bank: &BankState, path: &Path) -> Result<(), BankErrors> {
+    let json = serde_json::to_string_pretty(bank)
+        .map_err(|e| BankErrors::StorageProblem(format!("Cant serialize bank: {e}")))?;
+
+    //Write into a temporary file first, so a crash mid-write never corrupts the previous snapshot.
+    //Both the file and the rename reach the disk before this returns, the journal may be dropped after it
+    let tmp_path = path.with_extension("tmp");
+    File::create(&tmp_path)
+        .and_then(|mut file| file.write_all(json.as_bytes()).and_then(|_| file.sync_all()))
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .and_then(|_| sync_parent(path))
+        .map_err(|e| BankErrors::StorageProblem(format!("Cant write {}: {e}", path.display())))
+}
+
+//A rename lives in the directory entry, so the directory itself has to be flushed
+fn sync_parent(path: &Path) -> io::Result<()> {
+    //Windows cannot open a directory as a file
+    if cfg!(windows) {
+        return Ok(())
+    }
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    File::open(dir)?.sync_all()
+}
+
+pub fn load_bank(path: &Path) -> Result<Option<BankState>, BankErrors> {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(BankErrors::StorageProblem(format!("Cant read {}: {e}", path.display())))
+    };
+
+    let mut bank: BankState = serde_json::from_str(&json)
+        .map_err(|e| BankErrors::StorageProblem(format!("Cant parse {}: {e}", path.display())))?;
+    bank.rebuild_indexes();
+    bank.open_ledger();
+    Ok(Some(bank))
+}
+
+//Starts a brand new bank: an empty snapshot and an empty journal. Never writes over an existing bank
+pub fn create_bank(name: String, snapshot_path: &Path, journal_path: &Path) -> Result<BankState, BankErrors> {
+    let lock = BankLock::take(snapshot_path)?;
+    let exists = snapshot_path.try_exists()
+        .map_err(|e| BankErrors::StorageProblem(format!("Cant check {}: {e}", snapshot_path.display())))?;
+    if exists {
+        return Err(BankErrors::StorageProblem(format!("{} already holds a bank", snapshot_path.display())));
+    }
+    let (journal, records) = Journal::open(journal_path)?;
+    if !records.is_empty() {
+        return Err(BankErrors::StorageProblem(format!("{} already holds operations", journal_path.display())));
+    }
+
+    let mut bank = BankState::new(name);
+    bank.attach_journal(journal, Arc::new(lock));
+    compact(&mut bank, snapshot_path)?;
+    Ok(bank)
+}
+
+//Loads the last snapshot, replays the journal on top of it and folds the result into a new snapshot
+pub fn open_bank(snapshot_path: &Path, journal_path: &Path) -> Result<Option<BankState>, BankErrors> {
+    let lock = BankLock::take(snapshot_path)?;
+    let (journal, records) = Journal::open(journal_path)?;
+
+    let mut bank = match load_bank(snapshot_path)? {
+        Some(bank) => bank,
+        None if records.is_empty() => return Ok(None),
+        None => return Err(BankErrors::JournalCorrupted(format!("{} has no snapshot to replay on", journal_path.display())))
+    };
+
+    //Records older than the snapshot are already part of it (crash between snapshot and truncate)
+    for record in records {
+        bank.replay(record.seq, record.op);
+    }
+
+    bank.attach_journal(journal, Arc::new(lock));
+    compact(&mut bank, snapshot_path)?;
+    Ok(Some(bank))
+}
+
+//Writes the snapshot first and only then drops the journal, so a crash in between loses nothing
+pub fn compact(bank: &mut BankState, snapshot_path: &Path) -> Result<(), BankErrors> {
+    save_bank(bank, snapshot_path)?;
+    match bank.journal_mut() {
+        Some(journal) if !journal.is_empty() => journal.truncate(),
+        _ => Ok(())
+    }
+}
+
+//Operations are already in the journal, from time to time it is folded into the snapshot
+pub fn checkpoint(bank: &mut BankState, snapshot_path: &Path) -> Result<(), BankErrors> {
+    if bank.journal_mut().is_some_and(|journal| journal.len() >= COMPACT_AFTER) {
+        compact(bank, snapshot_path)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use crate::bank::{BankAccount, BankUser};
+    use crate::test_support::add_two_users;
+
+    fn temp_paths(name: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir();
+        let snapshot = dir.join(format!("{name}-{}.json", std::process::id()));
+        let journal = dir.join(format!("{name}-{}.journal", std::process::id()));
+        let _ = fs::remove_file(&snapshot);
+        let _ = fs::remove_file(&journal);
+        let _ = fs::remove_file(snapshot.with_extension("lock"));
+        (snapshot, journal)
+    }
+
+    fn cleanup(paths: &(PathBuf, PathBuf)) {
+        let _ = fs::remove_file(&paths.0);
+        let _ = fs::remove_file(&paths.1);
+        let _ = fs::remove_file(paths.0.with_extension("lock"));
+    }
+
+    fn assert_same_bank(left: &BankState, right: &BankState) {
+        assert_eq!(left.name, right.name);
+        assert_eq!(left.get_transactions().len(), right.get_transactions().len());
+        for (left_tx, right_tx) in left.get_transactions().iter().zip(right.get_transactions()) {
+            assert_eq!((left_tx.get_id(), left_tx.get_status()), (right_tx.get_id(), right_tx.get_status()));
+        }
+        assert_eq!(left.get_accounts().len(), right.get_accounts().len());
+        assert_eq!(left.get_ledger().get_postings(), right.get_ledger().get_postings());
+        assert_eq!(left.check_books(), Ok(()));
+        for (card, account) in right.get_accounts() {
+            assert_eq!(left.get_accounts()[card].check_balance(), account.check_balance());
+        }
+        for (id, customer) in right.get_customers() {
+            assert_eq!(left.get_customers()[id].check_fio(), customer.check_fio());
+            assert_eq!(left.get_customers()[id].check_cards(), customer.check_cards());
+        }
+    }
+
+    fn fill_bank(bank: &mut BankState) {
+        let (first, second) = add_two_users(bank);
+        bank.transfer_by_card(first.to_string(), second.to_string(), "10".to_string()).unwrap();
+        let _ = bank.transfer_by_card(second.to_string(), first.to_string(), "1000".to_string());
+        let tx = bank.transfer_by_card(second.to_string(), first.to_string(), "5".to_string()).unwrap();
+        bank.reverse_transaction(tx.get_id()).unwrap();
+    }
+
+    #[test]
+    fn saved_bank_is_loaded_back() {
+        let paths = temp_paths("bank-roundtrip");
+        let mut bank = BankState::new("Saved".to_string());
+        fill_bank(&mut bank);
+
+        save_bank(&bank, &paths.0).unwrap();
+        let loaded = load_bank(&paths.0).unwrap().unwrap();
+        cleanup(&paths);
+
+        assert_same_bank(&loaded, &bank);
+    }
+
+    #[test]
+    fn snapshot_without_ledger_gets_opening_entries() {
+        let paths = temp_paths("bank-no-ledger");
+        let mut bank = BankState::new("Old".to_string());
+        fill_bank(&mut bank);
+        let mut json = serde_json::to_value(&bank).unwrap();
+        json.as_object_mut().unwrap().remove("ledger");
+        fs::write(&paths.0, json.to_string()).unwrap();
+
+        let loaded = load_bank(&paths.0).unwrap().unwrap();
+        cleanup(&paths);
+
+        assert_eq!(loaded.get_ledger().get_postings().len(), 2 * bank.get_accounts().len());
+        assert_eq!(loaded.check_books(), Ok(()));
+    }
+
+    #[test]
+    fn missing_file_means_no_bank() {
+        let paths = temp_paths("bank-missing");
+        assert!(load_bank(&paths.0).unwrap().is_none());
+        assert!(open_bank(&paths.0, &paths.1).unwrap().is_none());
+    }
+
+    #[test]
+    fn journal_is_replayed_over_the_snapshot() {
+        let paths = temp_paths("bank-replay");
+        let mut bank = create_bank("Journaled".to_string(), &paths.0, &paths.1).unwrap();
+        fill_bank(&mut bank);
+        assert_eq!(bank.journal_mut().unwrap().len(), 6);
+
+        //The snapshot on disk is still the empty bank, everything else lives in the journal
+        assert!(load_bank(&paths.0).unwrap().unwrap().get_customers().is_empty());
+
+        bank.close();
+        let mut reopened = open_bank(&paths.0, &paths.1).unwrap().unwrap();
+        assert_same_bank(&reopened, &bank);
+
+        //Opening compacts: the snapshot holds everything and the journal is empty
+        assert!(reopened.journal_mut().unwrap().is_empty());
+        assert_same_bank(&load_bank(&paths.0).unwrap().unwrap(), &bank);
+        cleanup(&paths);
+    }
+
+    #[test]
+    fn records_already_in_the_snapshot_are_not_applied_twice() {
+        let paths = temp_paths("bank-crash-compaction");
+        let mut bank = create_bank("Journaled".to_string(), &paths.0, &paths.1).unwrap();
+        fill_bank(&mut bank);
+
+        //Crash after the snapshot was written but before the journal was truncated
+        save_bank(&bank, &paths.0).unwrap();
+
+        bank.close();
+        let reopened = open_bank(&paths.0, &paths.1).unwrap().unwrap();
+        cleanup(&paths);
+
+        assert_same_bank(&reopened, &bank);
+    }
+
+    #[test]
+    fn open_bank_is_locked_for_other_processes() {
+        let paths = temp_paths("bank-locked");
+        let bank = create_bank("Locked".to_string(), &paths.0, &paths.1).unwrap();
+
+        let error = open_bank(&paths.0, &paths.1).unwrap_err();
+        assert!(matches!(&error, BankErrors::StorageProblem(message) if message.contains("used by another process")));
+
+        drop(bank);
+        assert!(!paths.0.with_extension("lock").exists());
+        assert!(open_bank(&paths.0, &paths.1).unwrap().is_some());
+        cleanup(&paths);
+    }
+}
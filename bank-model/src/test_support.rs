@@ -0,0 +1,37 @@
+//Fixtures shared by the tests of every module
+use crate::bank::{BankState, BankUser};
+use crate::card::CardNumber;
+use crate::money::{Currency, Money};
+
+//Main card of the customer with this phone
+pub fn card_of(bank: &BankState, phone: &str) -> CardNumber {
+    bank.find_customer_by_phone(&phone.parse().unwrap()).unwrap().check_main_card()
+}
+
+//Ivanov with 100 and Petrov with 50 roubles, the starting point of most histories
+pub fn add_two_users(bank: &mut BankState) -> (CardNumber, CardNumber) {
+    bank.create_user("Ivanov I.I.".to_string(), "+79000000111".to_string(), "100".to_string()).unwrap();
+    bank.create_user("Petrov P.P.".to_string(), "+79000000222".to_string(), "50".to_string()).unwrap();
+    (card_of(bank, "+79000000111"), card_of(bank, "+79000000222"))
+}
+
+pub fn bank_with_two_users() -> (BankState, CardNumber, CardNumber) {
+    let mut bank = BankState::new("Test".to_string());
+    let (first, second) = add_two_users(&mut bank);
+    (bank, first, second)
+}
+
+//Whole roubles
+pub fn rub(major: u64) -> Money {
+    Money::new(major * 100, Currency::Rub)
+}
+
+//Roubles on all cards together
+pub fn total_money(bank: &BankState) -> Money {
+    bank.get_accounts().values()
+        .flat_map(|account| account.get_balances())
+        .filter(|money| money.currency() == Currency::Rub)
+        .copied()
+        .try_fold(Money::new(0, Currency::Rub), Money::checked_add)
+        .unwrap()
+}
@@ -0,0 +1,219 @@
+//Routes of the JSON API, kept apart from the HTTP plumbing in main.rs
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use bank_model::{BankAccount, BankErrors, BankState, BankUser, Customer, CustomerId, Transaction};
+use bank_model::card::CardNumber;
+use bank_model::phone::PhoneNumber;
+
+pub struct Reply {
+    pub status: u16,
+    pub body: Value,
+}
+impl Reply {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+    fn created(body: Value) -> Self {
+        Self { status: 201, body }
+    }
+    pub fn error(status: u16, code: &str, message: String) -> Self {
+        Self { status, body: json!({ "error": code, "message": message }) }
+    }
+}
+impl From<BankErrors> for Reply {
+    fn from(e: BankErrors) -> Self {
+        Reply::error(status_of(&e), e.code(), e.to_string())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NewUser {
+    fio: String,
+    phone: String,
+    #[serde(default)]
+    balance: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserChanges {
+    #[serde(default)]
+    fio: Option<String>,
+    #[serde(default)]
+    phone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CardTransfer {
+    from: String,
+    to: String,
+    amount: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PhoneTransfer {
+    from: String,
+    phone: String,
+    amount: String,
+}
+
+//Only GET requests are served here: they never change the bank, so they can run side by side.
+//`path` may carry a query string
+pub fn handle_get(bank: &BankState, path: &str) -> Reply {
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+
+    let result = match segments.as_slice() {
+        ["users"] => Ok(list_users(bank)),
+        ["users", id] => parse_id(id).and_then(|id| find_customer(bank, id)).map(|customer| Reply::ok(user_json(bank, customer))),
+        ["transactions"] => history(bank, query),
+        _ => Ok(unmatched("GET", path))
+    };
+    result.unwrap_or_else(|reply| reply)
+}
+
+//Every other method, `body` is the raw request body
+pub fn handle(bank: &mut BankState, method: &str, path: &str, body: &str) -> Reply {
+    let path = path.split_once('?').map_or(path, |(path, _)| path);
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+
+    let result = match (method, segments.as_slice()) {
+        ("POST", ["users"]) => parse_body(body).and_then(|user| create_user(bank, user)),
+        ("PUT", ["users", id]) => parse_id(id).and_then(|id| parse_body(body).and_then(|changes| update_user(bank, id, changes))),
+        ("DELETE", ["users", id]) => parse_id(id).and_then(|id| delete_user(bank, id)),
+        ("POST", ["transfers", "card"]) => parse_body(body).and_then(|transfer: CardTransfer| {
+            let tx = bank.transfer_by_card(transfer.from, transfer.to, transfer.amount)?;
+            Ok(Reply::created(transaction_json(&tx)))
+        }),
+        ("POST", ["transfers", "phone"]) => parse_body(body).and_then(|transfer: PhoneTransfer| {
+            let tx = bank.transfer_by_phone(transfer.from, transfer.phone, transfer.amount)?;
+            Ok(Reply::created(transaction_json(&tx)))
+        }),
+        _ => Ok(unmatched(method, path))
+    };
+    result.unwrap_or_else(|reply| reply)
+}
+
+fn unmatched(method: &str, path: &str) -> Reply {
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    match segments.as_slice() {
+        ["users"] | ["users", _] | ["transfers", "card" | "phone"] | ["transactions"] => {
+            Reply::error(405, "method_not_allowed", format!("{method} is not supported on {path}"))
+        }
+        _ => Reply::error(404, "not_found", format!("No such endpoint: {path}"))
+    }
+}
+
+//Client mistakes are 4xx, broken books or files are the server's problem
+fn status_of(e: &BankErrors) -> u16 {
+    match e {
+        BankErrors::InvalidAmountProblem
+        | BankErrors::AmountOverflow
+        | BankErrors::UnknownCurrency(_)
+        | BankErrors::CurrencyMismatch { .. }
+        | BankErrors::InvalidExchangeRate(_)
+        | BankErrors::InvalidCardProblem(_)
+        | BankErrors::InvalidPhone(_)
+        | BankErrors::InvalidDate(_)
+        | BankErrors::InvalidCsvRow(_) => 400,
+        BankErrors::CantFindUserByCard(_)
+        | BankErrors::CustomerNotFound(_)
+        | BankErrors::SenderNotFound(_)
+        | BankErrors::RecipientNotFound(_)
+        | BankErrors::TransactionNotFound(_) => 404,
+        BankErrors::PhoneAlreadyRegistered(_) => 409,
+        BankErrors::NoExchangeRate { .. }
+        | BankErrors::NotOwnAccount(_)
+        | BankErrors::NotEnoughMoney { .. }
+        | BankErrors::NotReversible(_) => 422,
+        BankErrors::LedgerUnbalanced(_)
+        | BankErrors::LedgerMismatch { .. }
+        | BankErrors::StorageProblem(_)
+        | BankErrors::JournalCorrupted(_) => 500,
+    }
+}
+
+fn parse_body<T: DeserializeOwned>(body: &str) -> Result<T, Reply> {
+    serde_json::from_str(body).map_err(|e| Reply::error(400, "invalid_json", format!("Invalid request body: {e}")))
+}
+
+fn parse_id(id: &str) -> Result<CustomerId, Reply> {
+    id.parse().map_err(|_| Reply::error(400, "invalid_id", format!("Invalid customer id: {id}")))
+}
+
+fn find_customer(bank: &BankState, id: CustomerId) -> Result<&Customer, Reply> {
+    Ok(bank.get_customers().get(&id).ok_or(BankErrors::CustomerNotFound(id))?)
+}
+
+fn list_users(bank: &BankState) -> Reply {
+    let mut customers: Vec<&Customer> = bank.get_customers().values().collect();
+    customers.sort_by_key(|customer| customer.get_id());
+    Reply::ok(customers.into_iter().map(|customer| user_json(bank, customer)).collect())
+}
+
+fn create_user(bank: &mut BankState, user: NewUser) -> Result<Reply, Reply> {
+    let phone: PhoneNumber = user.phone.parse()?;
+    bank.create_user(user.fio, user.phone, user.balance.unwrap_or_else(|| "0".to_string()))?;
+    let customer = bank.find_customer_by_phone(&phone).expect("customer was just created");
+    Ok(Reply::created(user_json(bank, customer)))
+}
+
+//The model addresses customers by any of their cards, the API by id
+fn update_user(bank: &mut BankState, id: CustomerId, changes: UserChanges) -> Result<Reply, Reply> {
+    let card = find_customer(bank, id)?.check_main_card();
+    bank.update_customer(card.digits(), changes.fio.unwrap_or_default(), changes.phone.unwrap_or_default())?;
+    Ok(Reply::ok(user_json(bank, find_customer(bank, id)?)))
+}
+
+fn delete_user(bank: &mut BankState, id: CustomerId) -> Result<Reply, Reply> {
+    let card = find_customer(bank, id)?.check_main_card();
+    bank.delete_user(card.digits())?;
+    Ok(Reply::ok(json!({ "deleted": id })))
+}
+
+//All transactions oldest first, `?card=` keeps the ones the card took part in
+fn history(bank: &BankState, query: &str) -> Result<Reply, Reply> {
+    let card = query.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "card")
+        .map(|(_, card)| card.parse::<CardNumber>())
+        .transpose()?;
+    let transactions = bank.get_transactions().iter()
+        .filter(|tx| card.is_none_or(|card| tx.involves(card)))
+        .map(transaction_json)
+        .collect();
+    Ok(Reply::ok(transactions))
+}
+
+fn user_json(bank: &BankState, customer: &Customer) -> Value {
+    let cards: Vec<Value> = bank.get_customer_accounts(customer)
+        .map(|account| json!({
+            "card": account.check_card_number().digits(),
+            "balance": account.check_balance().amount(),
+            "currency": account.check_currency().code(),
+        }))
+        .collect();
+    json!({
+        "id": customer.get_id(),
+        "fio": customer.check_fio(),
+        "phone": customer.check_phone(),
+        "cards": cards,
+    })
+}
+
+fn transaction_json(tx: &Transaction) -> Value {
+    json!({
+        "id": tx.get_id(),
+        "time": tx.get_tx_time().to_rfc3339(),
+        "kind": tx.get_kind().code(),
+        "status": tx.get_status().code(),
+        "amount": tx.get_amount().amount(),
+        "currency": tx.get_amount().currency().code(),
+        "sender_card": tx.get_sender_card().map(|card| card.digits()),
+        "recipient": tx.get_recipient().to_string(),
+        "recipient_card": tx.get_recipient_card().map(|card| card.digits()),
+        "credited_amount": tx.get_credited_amount().amount(),
+        "credited_currency": tx.get_credited_amount().currency().code(),
+    })
+}
@@ -0,0 +1,94 @@
+//JSON over HTTP access to the same bank files the GUI works with, for our other tools
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::thread;
+use clap::Parser;
+use tiny_http::{Header, Method, Response, Server};
+use bank_model::{BankErrors, SharedBank};
+use bank_model::journal::JOURNAL_FILE;
+use bank_model::storage::{BANK_FILE, checkpoint, create_bank, open_bank};
+
+mod api;
+
+const WORKERS: usize = 4;
+
+#[derive(Debug, Parser)]
+#[command(name = "bank-server", about = "Serve the bank as a JSON API on localhost")]
+struct Args {
+    /// Snapshot of the bank
+    #[arg(long, default_value = BANK_FILE)]
+    bank: PathBuf,
+    /// Journal of operations made after the snapshot
+    #[arg(long, default_value = JOURNAL_FILE)]
+    journal: PathBuf,
+    /// Address to listen on, port 0 picks a free one
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    addr: String,
+    /// Name for a new bank if there is none yet
+    #[arg(long, default_value = "Bank")]
+    name: String,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), BankErrors> {
+    let bank = match open_bank(&args.bank, &args.journal)? {
+        Some(bank) => bank,
+        None => create_bank(args.name, &args.bank, &args.journal)?
+    };
+    let server = Server::http(&args.addr)
+        .map_err(|e| BankErrors::StorageProblem(format!("Cant listen on {}: {e}", args.addr)))?;
+    //The actual address goes first, so whoever started us with port 0 knows where to connect
+    if let Some(addr) = server.server_addr().to_ip() {
+        println!("Listening on http://{addr}");
+    }
+
+    //Several clients are served at once, all of them over the same bank
+    let bank = SharedBank::new(bank);
+    let server = Arc::new(server);
+    let workers: Vec<_> = (0..WORKERS).map(|_| {
+        let (bank, server, snapshot_path) = (bank.clone(), Arc::clone(&server), args.bank.clone());
+        thread::spawn(move || serve(&server, &bank, &snapshot_path))
+    }).collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    Ok(())
+}
+
+fn serve(server: &Server, bank: &SharedBank, snapshot_path: &Path) {
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let reply = match request.as_reader().read_to_string(&mut body) {
+            //Reads share the bank, only changes wait for each other and may compact the journal
+            Ok(_) if *request.method() == Method::Get => bank.read(|bank| api::handle_get(bank, request.url())),
+            Ok(_) => bank.write(|bank| {
+                let reply = api::handle(bank, request.method().as_str(), request.url(), &body);
+                if let Err(e) = checkpoint(bank, snapshot_path) {
+                    eprintln!("{e}")
+                }
+                reply
+            }),
+            Err(e) => api::Reply::error(400, "invalid_body", format!("Cant read request body: {e}"))
+        };
+
+        let json_header = Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+        let response = Response::from_string(reply.body.to_string())
+            .with_status_code(reply.status)
+            .with_header(json_header);
+        if let Err(e) = request.respond(response) {
+            eprintln!("Cant send response: {e}");
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use serde_json::{json, Value};
+
+struct BankServer {
+    dir: PathBuf,
+    child: Child,
+    url: String,
+}
+impl BankServer {
+    fn start(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("bank-server-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut child = Command::new(env!("CARGO_BIN_EXE_bank-server"))
+            .arg("--bank").arg(dir.join("bank.json"))
+            .arg("--journal").arg(dir.join("bank.journal"))
+            .arg("--addr").arg("127.0.0.1:0")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut first_line = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut first_line).unwrap();
+        let url = first_line.trim().strip_prefix("Listening on ").expect("server prints its address").to_string();
+        Self { dir, child, url }
+    }
+
+    fn send(&self, method: &str, path: &str, body: Option<Value>) -> (u16, Value) {
+        let request = ureq::request(method, &format!("{}{path}", self.url));
+        let result = match body {
+            Some(body) => request.send_json(body),
+            None => request.call()
+        };
+        let response = match result {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => panic!("{method} {path} failed: {e}")
+        };
+        (response.status(), response.into_json().unwrap())
+    }
+}
+impl Drop for BankServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[test]
+fn users_and_transfers_over_http() {
+    let server = BankServer::start("flow");
+    let (status, ivanov) = server.send("POST", "/users", Some(json!({ "fio": "Ivanov I.I.", "phone": "+79000000111", "balance": "100" })));
+    assert_eq!(status, 201);
+    let (_, petrov) = server.send("POST", "/users", Some(json!({ "fio": "Petrov P.P.", "phone": "89000000222" })));
+    let first = ivanov["cards"][0]["card"].as_str().unwrap().to_string();
+    let second = petrov["cards"][0]["card"].as_str().unwrap().to_string();
+
+    let (status, tx) = server.send("POST", "/transfers/card", Some(json!({ "from": first, "to": second, "amount": "30" })));
+    assert_eq!(status, 201);
+    assert_eq!(tx["status"], "completed");
+    assert_eq!(tx["amount"], "30.00");
+    server.send("POST", "/transfers/phone", Some(json!({ "from": second, "phone": "+79000000111", "amount": "5.50" })));
+
+    let (status, users) = server.send("GET", "/users", None);
+    assert_eq!(status, 200);
+    assert_eq!(users[0]["cards"][0]["balance"], "75.50");
+    assert_eq!(users[1]["cards"][0]["balance"], "24.50");
+
+    let (_, updated) = server.send("PUT", &format!("/users/{}", petrov["id"]), Some(json!({ "fio": "Petrov P.A." })));
+    assert_eq!(updated["fio"], "Petrov P.A.");
+    assert_eq!(updated["phone"], "+79000000222");
+
+    let (_, history) = server.send("GET", &format!("/transactions?card={second}"), None);
+    assert_eq!(history.as_array().unwrap().len(), 2);
+
+    let (status, _) = server.send("DELETE", &format!("/users/{}", petrov["id"]), None);
+    assert_eq!(status, 200);
+    let (status, _) = server.send("GET", &format!("/users/{}", petrov["id"]), None);
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn errors_come_back_as_json() {
+    let server = BankServer::start("errors");
+    let (_, ivanov) = server.send("POST", "/users", Some(json!({ "fio": "Ivanov I.I.", "phone": "+79000000111", "balance": "10" })));
+    let card = ivanov["cards"][0]["card"].as_str().unwrap().to_string();
+
+    let (status, error) = server.send("POST", "/users", Some(json!({ "fio": "Someone", "phone": "+79000000111" })));
+    assert_eq!(status, 409);
+    assert_eq!(error["error"], "phone_already_registered");
+
+    let (status, error) = server.send("POST", "/transfers/phone", Some(json!({ "from": card, "phone": "+79000000999", "amount": "5" })));
+    assert_eq!(status, 404);
+    assert_eq!(error["error"], "recipient_not_found");
+
+    let (status, error) = server.send("POST", "/transfers/phone", Some(json!({ "from": card, "phone": "+79000000111", "amount": "50" })));
+    assert_eq!(status, 422);
+    assert_eq!(error["error"], "not_enough_money");
+    assert!(error["message"].as_str().unwrap().contains("Not enough money"));
+
+    let (status, error) = server.send("POST", "/transfers/card", Some(json!({ "from": card })));
+    assert_eq!(status, 400);
+    assert_eq!(error["error"], "invalid_json");
+
+    let (status, error) = server.send("GET", "/users/42", None);
+    assert_eq!(status, 404);
+    assert_eq!(error["error"], "customer_not_found");
+
+    let (status, _) = server.send("GET", "/accounts", None);
+    assert_eq!(status, 404);
+}
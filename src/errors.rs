@@ -1,5 +0,0 @@
-pub enum BankErrors {
-    InvalidAmountProblem,
-    TransferProblem,
-    CantFindUserByCard(String),
-}
\ No newline at end of file
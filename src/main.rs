@@ -1,23 +1,32 @@
-use std::fmt::format;
 //Model
-use crate::bank_model::{BankState, BankUser, User, Transaction};
+use bank_model::{BankErrors, BankState, TransactionId};
+use bank_model::statement;
+//Persistence
+use bank_model::journal::JOURNAL_FILE;
+use bank_model::storage::{BANK_FILE, checkpoint, create_bank, open_bank};
+use std::path::Path;
+//Pages
+use crate::router::{Route, Router};
+mod pages;
+mod router;
+//Notifications
+use crate::toast::Toasts;
+mod toast;
 //Styles
-use crate::styles::{ContainerStyle};
-mod bank_model;
-mod errors;
 mod styles;
+//Input checks
+mod validation;
 
 
 //iced crate dependencies
-use iced::{Alignment, alignment, Application, Element, Length, Padding, Renderer, Sandbox, Settings, Size, Theme, window};
-use iced::alignment::{Horizontal, Vertical};
+use iced::{Alignment, alignment, Application, Color, Command, Element, executor, Length, Settings, Size, Subscription, Theme, window};
 use iced::theme::{Button};
-use iced::widget::{button, text_input, container, column, text, row, Space, Container, Row, Scrollable, Column};
-use iced::widget::scrollable::{Direction, Properties};
+use iced::widget::{button, text_input, container, column, text, row, Scrollable, Space};
+use std::time::{Duration, Instant};
 
 
 fn main() -> iced::Result {
-    <BankApp as Sandbox>::run(Settings {
+    BankApp::run(Settings {
         window: window::Settings {
             size: Size::new(1200.0, 800.0),
             resizable: true,
@@ -28,17 +37,49 @@ fn main() -> iced::Result {
 }
 
 //Our State of Bank Application
+#[derive(Debug, Default)]
+struct BankApp {
+    screen: Screen,
+    //Shown over every screen until they expire or get closed
+    toasts: Toasts
+}
+
 #[derive(Debug)]
-enum BankApp {
+enum Screen {
     BeforeBankCreating(InitialBankState),
-    OnlyBankCreated(BankState),
-    BankWithUsers(BankState),
-    TransferPage(BankState, TransferPageState)
+    Opened(Box<OpenedBank>),
+    //The files are there but could not be read. Creating a bank now would write over them,
+    //so there is nothing to do here until they are fixed by hand
+    Unreadable(BankErrors)
+}
+
+//The bank is owned here once, switching pages never touches it
+#[derive(Debug)]
+struct OpenedBank {
+    bank: BankState,
+    router: Router,
+    //Operation the pages last saw, see `Page::bank_changed`
+    seen_operation: u64
+}
+impl OpenedBank {
+    fn new(bank: BankState) -> Self {
+        let start = if bank.get_customers().is_empty() { Route::Customers } else { Route::Dashboard };
+        let mut router = Router::new(pages::registry(), start);
+        router.bank_changed(&bank, &bank.check_books());
+        Self { seen_operation: bank.last_operation(), bank, router }
+    }
+    fn sync_pages(&mut self) {
+        if self.bank.last_operation() != self.seen_operation {
+            self.seen_operation = self.bank.last_operation();
+            self.router.bank_changed(&self.bank, &self.bank.check_books());
+        }
+    }
 }
+
 //Initialize our first state of app
-impl Default for BankApp {
+impl Default for Screen {
     fn default() -> Self {
-        BankApp::BeforeBankCreating(
+        Screen::BeforeBankCreating(
             InitialBankState {
                 input_value: String::new(),
             }
@@ -57,66 +98,80 @@ enum BankMessage {
     //Bank's messages
     BankNameChanged(String),
     CreateBank,
+    //Navigation
+    Navigate(Route),
+    Back,
+    Forward,
+    //Notifications
+    DismissToast(u64),
+    ExpireToasts(Instant),
     //User's messages
     UserFioChanged(String),
     UserPhoneNumChanged(String),
     UserMoneyChanged(String),
     UserCardNumChanged(String),
-    FirstUserCreate,
+    ToggleCardNumbers,
     CreateUser,
     DeleteUser,
+    UpdateUser,
+    SearchChanged(String),
+    ShowCustomersPage(usize),
+    OpenAccount,
+    CloseAccount,
+    ImportPathChanged(String),
+    ImportCustomers,
+    ImportTransfers,
+    CashCardChanged(String),
+    CashAmountChanged(String),
+    Deposit,
+    Withdraw,
     //TransferPage's messages
     FromUserChanged(String),
     ToUserPhoneChanged(String),
     ToUserCardChanged(String),
     TransferAmountChanged(String),
-    ToTransferPage,
     TransferByCard,
     TransferByPhone,
-    ToUserPage,
+    TransferBetweenOwnCards,
     ByPhoneMode,
-    ByCardMode
-}
-
-#[derive(Debug)]
-struct TransferPageState {
-    sender_card_input: String,
-    amount_input: String,
-    recipient_card_input: String,
-    recipient_phone_input: String,
-    transfer_mode: TransferMode
-}
-impl Default for TransferPageState {
-    fn default() -> Self {
-        Self {
-            sender_card_input: String::new(),
-            amount_input: String::new(),
-            recipient_card_input: String::new(),
-            recipient_phone_input: String::new(),
-            transfer_mode: TransferMode::default()
-        }
-    }
-}
-impl TransferPageState {
-    fn reset_inputs(&mut self ) {
-        self.sender_card_input.clear();
-        self.amount_input.clear();
-        self.recipient_card_input.clear();
-        self.recipient_phone_input.clear();
-    }
-}
-#[derive(Debug, Default)]
-enum TransferMode {
-    #[default]
-    TransferByPhone,
-    TransferByCard
+    ByCardMode,
+    ByOwnCardsMode,
+    ReverseTransaction(TransactionId),
+    //HistoryPage's messages
+    StatementCardChanged(String),
+    StatementFromChanged(String),
+    StatementToChanged(String),
+    StatementMinChanged(String),
+    StatementMaxChanged(String),
+    StatementDirection(Option<statement::Direction>),
+    //ReportsPage's messages
+    ExportCardChanged(String),
+    ExportFromChanged(String),
+    ExportToChanged(String),
+    ExportCsv,
+    //SettingsPage's messages
+    CardBinChanged(String),
+    SetCardBin,
+    RateFromChanged(String),
+    RateToChanged(String),
+    RateValueChanged(String),
+    SetExchangeRate
 }
 
-impl Sandbox for BankApp {
+impl Application for BankApp {
+    type Executor = executor::Default;
     type Message = BankMessage;
-    
-    fn new() -> Self {
-        Self::default()
+    type Theme = Theme;
+    type Flags = ();
+
+    fn new(_flags: ()) -> (Self, Command<BankMessage>) {
+        let mut app = Self::default();
+        match open_bank(Path::new(BANK_FILE), Path::new(JOURNAL_FILE)) {
+            Ok(Some(bank)) => app.screen = Screen::Opened(Box::new(OpenedBank::new(bank))),
+            Ok(None) => {},
+            Err(e) => app.screen = Screen::Unreadable(e)
+        }
+        (app, Command::none())
     }
 
     fn title(&self) -> String {
@@ -127,45 +182,36 @@ impl Sandbox for BankApp {
         Theme::CatppuccinMacchiato
     }
 
-    fn update(&mut self, message: BankMessage) {
-        match self {
-            BankApp::BeforeBankCreating(InitialBankState) => {
-                match message {
-
-                    BankMessage::BankNameChanged(value) => {
-                        InitialBankState.input_value = value;
-                    }
-
-                    BankMessage::CreateBank => {
-                        let bank = BankState::new(InitialBankState.input_value.clone());
-                        *self = BankApp::OnlyBankCreated(bank)
-                    }
+    //The clock only runs while there is something to expire
+    fn subscription(&self) -> Subscription<BankMessage> {
+        if self.toasts.is_empty() {
+            Subscription::none()
+        } else {
+            iced::time::every(Duration::from_millis(500)).map(BankMessage::ExpireToasts)
+        }
+    }
 
-                    _ => {}
-                }
-            }
+    fn update(&mut self, message: BankMessage) -> Command<BankMessage> {
+        let BankApp { screen, toasts } = self;
+        match (screen, message) {
+            (_, BankMessage::DismissToast(id)) => toasts.dismiss(id),
+            (_, BankMessage::ExpireToasts(now)) => toasts.expire(now),
+            (Screen::Unreadable(_), _) => {}
 
-            BankApp::OnlyBankCreated(BankState) => {
+            (Screen::BeforeBankCreating(initial_state), message) => {
                 match message {
-                    BankMessage::UserFioChanged(fio) => {
-                        BankState.input_fio = fio;
-                    }
-
-                    BankMessage::UserPhoneNumChanged(phone) => {
-                        BankState.input_phone = phone;
-                    }
 
-                    BankMessage::UserMoneyChanged(money) => {
-                        BankState.input_money = money;
+                    BankMessage::BankNameChanged(value) => {
+                        initial_state.input_value = value;
                     }
 
-                    BankMessage::FirstUserCreate => {
-                        match BankState.create_user(BankState.input_fio.clone(), BankState.input_phone.clone(), BankState.input_money.clone()) {
-                            Ok(()) => {
-                                BankState.reset_inputs();
-                                *self = BankApp::BankWithUsers(BankState.clone());
+                    BankMessage::CreateBank => {
+                        match create_bank(initial_state.input_value.clone(), Path::new(BANK_FILE), Path::new(JOURNAL_FILE)) {
+                            Ok(bank) => {
+                                toasts.success(format!("Банк {} создан", bank.name));
+                                self.screen = Screen::Opened(Box::new(OpenedBank::new(bank)))
                             },
-                            Err(e) => println!("Invalid amount was given")
+                            Err(e) => toasts.error(&e)
                         }
                     }
 
@@ -173,110 +219,28 @@ impl Sandbox for BankApp {
                 }
             }
 
-            BankApp::BankWithUsers(BankState) => {
+            (Screen::Opened(opened), message) => {
                 match message {
-                    BankMessage::TransferByPhone => {
-                        todo!()
-                    }
-
-                    BankMessage::UserFioChanged(fio) => {
-                        BankState.input_fio = fio;
-                    }
-
-                    BankMessage::UserPhoneNumChanged(phone) => {
-                        BankState.input_phone = phone;
-                    }
-
-                    BankMessage::UserMoneyChanged(money) => {
-                        BankState.input_money = money;
-                    }
-
-                    BankMessage::UserCardNumChanged(card) => {
-                        BankState.input_card_num = card;
-                    }
-
-                    BankMessage::CreateUser => {
-                        let new_user = BankState.create_user(BankState.input_fio.clone(), BankState.input_phone.clone(), BankState.input_money.clone());
-                        BankState.reset_inputs();
-                    }
-
-                    BankMessage::DeleteUser => {
-                        match BankState.delete_user(BankState.input_card_num.clone()) {
-                            Ok(()) => {},
-                            Err(e) => {
-                                panic!("Cant find particular user")
-                            }
+                    BankMessage::Navigate(route) => opened.router.history.go(route),
+                    BankMessage::Back => opened.router.history.back(),
+                    BankMessage::Forward => opened.router.history.forward(),
+                    //Everything else belongs to the open page
+                    message => {
+                        opened.router.page_mut().update(&mut opened.bank, toasts, message);
+                        opened.sync_pages();
+                        if let Err(e) = checkpoint(&mut opened.bank, Path::new(BANK_FILE)) {
+                            toasts.error(&e)
                         }
                     }
-
-                    BankMessage::ToTransferPage => {
-                        *self = BankApp::TransferPage(BankState.clone(), TransferPageState::default())
-                    }
-
-                    _ => {}
-                }
-
-            }
-
-            BankApp::TransferPage(BankState, TransferPageState) => {
-                match message {
-                    BankMessage::ByPhoneMode => {
-                        TransferPageState.transfer_mode = TransferMode::TransferByPhone
-                    }
-
-                    BankMessage::ByCardMode => {
-                        TransferPageState.transfer_mode = TransferMode::TransferByCard
-                    }
-
-                    BankMessage::FromUserChanged(card) => {
-                        TransferPageState.sender_card_input = card;
-                    }
-
-                    BankMessage::ToUserCardChanged(card) => {
-                        TransferPageState.recipient_card_input = card;
-                    }
-
-                    BankMessage::ToUserPhoneChanged(phone_num) => {
-                        TransferPageState.recipient_phone_input = phone_num;
-                    }
-
-                    BankMessage::TransferAmountChanged(amount) => {
-                        TransferPageState.amount_input = amount;
-                    }
-
-                    BankMessage::TransferByPhone => {
-                        let tx = BankState.transfer_by_phone(
-                            TransferPageState.sender_card_input.clone(),
-                            TransferPageState.recipient_phone_input.clone(),
-                            TransferPageState.amount_input.clone()
-                        );
-                        BankState.add_transaction(tx);
-                        TransferPageState.reset_inputs()
-                    }
-
-                    BankMessage::TransferByCard => {
-                        let tx = BankState.transfer_by_card(
-                            TransferPageState.sender_card_input.clone(),
-                            TransferPageState.recipient_card_input.clone(),
-                            TransferPageState.amount_input.clone()
-                        );
-                        BankState.add_transaction(tx);
-                        TransferPageState.reset_inputs()
-                    }
-
-                    BankMessage::ToUserPage => {
-                        *self = BankApp::BankWithUsers(BankState.clone())
-                    }
-
-                    _ => {}
                 }
             }
         }
+        Command::none()
     }
 
-    fn view(&self) -> Element<BankMessage> {
-        match self {
-            BankApp::BeforeBankCreating(InitialBankState {
+    fn view(&self) -> Element<'_, BankMessage> {
+        match &self.screen {
+            Screen::BeforeBankCreating(InitialBankState {
                 input_value }) => {
                 let title = text("Bank App")
                     .size(50)
@@ -300,247 +264,52 @@ impl Sandbox for BankApp {
                     .align_items(Alignment::Center)
                     .width(Length::Fill);
 
-                container(content).height(Length::Fill).center_x().align_y(alignment::Vertical::Center).into()
-
-
-            }
-
-            BankApp::OnlyBankCreated(Bank) => {
-                let name = Bank.name.clone();
-
-                let bank_label = text(format!("Your Bank {name} was created"))
-                    .size(50);
-
-                let users_label = text("Users are empty...").size(35);
-
-                let user_label = text("Try to create some Users")
-                    .size(35);
-
-                let input = |text, input| {
-                    let input = text_input(text, input)
-                        .width(300)
-                        .padding(15)
-                        .size(15);
-                    input
-                };
+                let screen = container(content).height(Length::Fill).center_x().align_y(alignment::Vertical::Center);
+                column![self.toasts.view(), screen].into()
 
-                let inputs = row![
-                    input("Write the FIO", &Bank.input_fio).on_input(BankMessage::UserFioChanged),
-                    input("Write the Phone number", &Bank.input_phone).on_input(BankMessage::UserPhoneNumChanged),
-                    input("Write the Initial money", &Bank.input_money).on_input(BankMessage::UserMoneyChanged),
-                ].spacing(10);
 
-
-                let create_user_btn = button(text("Create User"))
-                    .padding(20)
-                    .on_press(BankMessage::FirstUserCreate);
-
-                container(
-                    column![
-                        Space::with_height(15),
-                        bank_label,
-                        Space::with_height(30),
-                        users_label,
-                        Space::with_height(50),
-                        user_label,
-                        inputs,
-                        create_user_btn]
-                        .spacing(35)
-                        .align_items(Alignment::Center)
-                        .width(Length::Fill)
-                ).align_x(Horizontal::Center).into()
             }
 
-            BankApp::BankWithUsers(Bank) => {
-
-                let users = Bank.get_users();
-                let label = text("Welcome to User's constructor")
-                    .size(50)
-                    .width(Length::Fill)
-                    .horizontal_alignment(alignment::Horizontal::Center);
-                let label_user = text("Bank have some Users")
-                    .size(30)
-                    .width(Length::Fill)
-                    .horizontal_alignment(alignment::Horizontal::Center);
-
-                let users_vec:Vec<Element<'_,BankMessage, Theme, Renderer>> = {
-                    users.iter().map(|(_, user)| user.view().into()).collect()
-                };
-
-                let users_container = Row::from_vec(users_vec).spacing(10);
-
-                let scrollable = Scrollable::new(users_container)
-                    .width(Length::Fill)
-                    .height(Length::Shrink)
-                    .direction(Direction::Horizontal(Properties::new()));
-
-                let input = |text, input| {
-                    let input = text_input(text, input)
-                        .width(300)
-                        .padding(15)
-                        .size(15);
-                    input
-                };
-
-                let inputs = row![
-                    input("Write the FIO", &Bank.input_fio).on_input(BankMessage::UserFioChanged),
-                    input("Write the Phone number", &Bank.input_phone).on_input(BankMessage::UserPhoneNumChanged),
-                    input("Write the Initial money", &Bank.input_money).on_input(BankMessage::UserMoneyChanged),
-                ].spacing(10);
-
-
-                let create_user_btn = button(text("Create User"))
-                    .padding(20)
-                    .on_press(BankMessage::CreateUser);
-
-                let to_transfer_page_btn = button(text("Переводы ->"))
-                    .padding(20)
-                    .on_press(BankMessage::ToTransferPage);
-
-                let button_row = row![create_user_btn, to_transfer_page_btn]
-                    .spacing(10)
-                    .align_items(Alignment::Center);
-
-                let deleted_interface = row![
-                    input("Write the Card", &Bank.input_card_num).on_input(BankMessage::UserCardNumChanged),
-                    button(text("Delete User"))
-                        .padding(20)
-                        .on_press(BankMessage::DeleteUser)
-                ].align_items(Alignment::Center).spacing(10);
-
-                container(column![Space::with_height(15), label, label_user, scrollable, inputs, button_row, deleted_interface].align_items(Alignment::Center).spacing(35)).align_y(alignment::Vertical::Center).into()
-
-
+            Screen::Unreadable(e) => {
+                let content = column![
+                    text("Не удалось открыть банк").size(50),
+                    text(e.to_string()).size(20).style(Color::from_rgb8(237, 135, 150)),
+                    text(format!("Исправьте или перенесите {BANK_FILE} и {JOURNAL_FILE} и запустите приложение снова")).size(15)
+                ].spacing(25).align_items(Alignment::Center).width(Length::Fill);
+                container(content).height(Length::Fill).center_x().align_y(alignment::Vertical::Center).into()
             }
 
-            BankApp::TransferPage(Bank, TransferPageState) => {
-
-                let transactions = Bank.get_transactions();
-
-                let intro_text = text("Welcome to Transfers")
-                    .size(50)
+            Screen::Opened(opened) => {
+                let page = Scrollable::new(opened.router.page().view(&opened.bank))
                     .width(Length::Fill)
-                    .horizontal_alignment(Horizontal::Center);
-
-                let phone_mode_btn = button("По номеру телефона")
-                    .padding(20)
-                    .on_press(BankMessage::ByPhoneMode);
-
-                let card_mode_btn = button("По номеру карты")
-                    .padding(20)
-                    .on_press(BankMessage::ByCardMode);
-
-                let input = |text, input| {
-                    let input = text_input(text, input)
-                        .width(300)
-                        .padding(15)
-                        .size(15);
-                    input
-                };
-
-                let transfer_by_phone_btn = button("Перевод по номеру")
-                    .padding(20)
-                    .on_press(BankMessage::TransferByPhone);
-
-                let transfer_by_card_btn = button("Перевод по карте")
-                    .padding(20)
-                    .on_press(BankMessage::TransferByCard);
-
-                let to_user_page_btn = button("<- Пользователи")
-                    .padding(20)
-                    .on_press(BankMessage::ToUserPage);
-
-                let to_user_page_btn1 = button("<- Пользователи")
-                    .padding(20)
-                    .on_press(BankMessage::ToUserPage);
-
-                let phone_mode = column![
-                    input("User from...", &TransferPageState.sender_card_input).on_input(BankMessage::FromUserChanged),
-                    input("User to (Phone number)...", &TransferPageState.recipient_phone_input).on_input(BankMessage::ToUserPhoneChanged),
-                    input("Amount to send...", &TransferPageState.amount_input).on_input(BankMessage::TransferAmountChanged),
-                    row![to_user_page_btn1, transfer_by_phone_btn].spacing(5)
-                ].spacing(20);
-
-                let card_mode = column![
-                    input("User from...", &TransferPageState.sender_card_input).on_input(BankMessage::FromUserChanged),
-                    input("User to (Card number)...", &TransferPageState.recipient_card_input).on_input(BankMessage::ToUserCardChanged),
-                    input("Amount to send...", &TransferPageState.amount_input).on_input(BankMessage::TransferAmountChanged),
-                    row![to_user_page_btn, transfer_by_card_btn].spacing(5)
-                ].spacing(20);
-
-                let tx_vec: Vec<Element<'_, BankMessage, Theme, Renderer>> = {
-                    transactions.iter().map(|tx| tx.view().into()).collect()
-                };
-
-                let tx_row = Row::from_vec(tx_vec).spacing(10);
-
-                let scrollbar = Scrollable::new(tx_row)
-                    .width(Length::Fill)
-                    .height(Length::Shrink)
-                    .direction(Direction::Horizontal(Properties::new()));
-
-                let container = container(
-                    column![
-                        Space::with_height(20),
-                        intro_text,
-                        Space::with_height(40),
-                        row![card_mode_btn, phone_mode_btn].spacing(5),
-                        {
-                            match TransferPageState.transfer_mode {
-                                TransferMode::TransferByPhone => {
-                                    phone_mode
-                                },
-                                TransferMode::TransferByCard => {
-                                    card_mode
-                                }
-                            }
-                        },
-                        scrollbar
-
-                    ].spacing(10).align_items(Alignment::Center)
-                ).align_y(Vertical::Center).align_x(Horizontal::Center).into();
-
-                container
+                    .height(Length::Fill);
+                row![sidebar(&opened.router), column![self.toasts.view(), page]].into()
             }
         }
     }
 }
 
-impl User {
-    fn view(&self) -> Container<'_, BankMessage, Theme, Renderer> {
-        let card_number = self.check_card_number();
-        let fio = self.check_fio();
-        let phone_number = self.check_phone();
-        let balance = self.check_balance();
-
-        let text_fio = text(format!("Ф.И.О: {fio}")).size(12);
-        let text_phone = text(format!("Телефон: {phone_number}")).size(12);
-        let text_card_num = text(format!("Номер карты: {card_number}")).size(12);
-        let text_balance = text(format!("Баланс: {balance}")).size(12);
-
-        container(column![text_fio, text_phone, text_card_num, text_balance].align_items(Alignment::Start).spacing(10))
-            .center_y()
-            .width(150)
-            .height(120)
-            .style(iced::theme::Container::Custom(Box::new(ContainerStyle)))
-    }
-}
-impl Transaction {
-    fn view(&self) -> Container<'_, BankMessage, Theme, Renderer> {
-        let date = self.get_tx_time();
-        let amount = self.get_amount();
-        let sender = self.get_sender_card();
-        let recipient = self.get_recipient();
-
-        let text_date = text(format!("Дата: {date}")).size(12);
-        let text_amount = text(format!("Сумма: {amount}")).size(12);
-        let text_sender = text(format!("Отправитель: {sender}")).size(12);
-        let text_recipient = text(format!("Получатель: {recipient}")).size(12);
-        container(column![text_date, text_amount, text_sender, text_recipient].align_items(Alignment::Start).spacing(10))
-            .center_y()
-            .width(150)
-            .height(120)
-            .style(iced::theme::Container::Custom(Box::new(ContainerStyle)))
-    }
-}
+//Always on the left: back/forward and a button per page, the open one highlighted
+fn sidebar(router: &Router) -> Element<'_, BankMessage> {
+    let history = &router.history;
+    let arrows = row![
+        button(text("<-")).padding(10).on_press_maybe(history.can_go_back().then_some(BankMessage::Back)),
+        button(text("->")).padding(10).on_press_maybe(history.can_go_forward().then_some(BankMessage::Forward))
+    ].spacing(5);
+
+    let links = router.routes().fold(column![arrows, Space::with_height(20)].spacing(10), |links, route| {
+        links.push(
+            button(text(route.label()))
+                .width(Length::Fill)
+                .padding(15)
+                .style(if route == history.current() { Button::Primary } else { Button::Secondary })
+                .on_press(BankMessage::Navigate(route))
+        )
+    });
 
+    container(links)
+        .width(180)
+        .height(Length::Fill)
+        .padding(10)
+        .into()
+}
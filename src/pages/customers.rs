@@ -0,0 +1,333 @@
+use std::path::Path;
+use iced::{Alignment, alignment, Color, Element, Length, Renderer, Theme};
+use iced::widget::{button, column, container, row, text, text_input, Container, Row, Scrollable, Space};
+use iced::widget::scrollable::{Direction, Properties};
+use bank_model::{Account, BankAccount, BankState, BankUser, Customer};
+use bank_model::import::{ImportReport, import_customers, import_file, import_transfers};
+use crate::BankMessage;
+use crate::router::Page;
+use crate::styles::ContainerStyle;
+use crate::toast::Toasts;
+use crate::validation::{self, field};
+
+//Cards of this many customers are drawn at once, the rest is reached page by page
+const CUSTOMERS_PER_PAGE: usize = 20;
+
+#[derive(Debug, Default)]
+pub struct CustomersPage {
+    input_fio: String,
+    input_phone: String,
+    input_money: String,
+    input_card_num: String,
+    input_search: String,
+    input_cash_card: String,
+    input_cash_amount: String,
+    input_import_path: String,
+    import_report: Option<ImportReport>,
+    show_card_numbers: bool,
+    customers_page: usize
+}
+impl CustomersPage {
+    fn reset_inputs(&mut self) {
+        self.input_phone.clear();
+        self.input_fio.clear();
+        self.input_money.clear();
+    }
+}
+
+impl Page for CustomersPage {
+    fn update(&mut self, bank: &mut BankState, toasts: &mut Toasts, message: BankMessage) {
+        match message {
+            BankMessage::UserFioChanged(fio) => {
+                self.input_fio = fio;
+            }
+
+            BankMessage::UserPhoneNumChanged(phone) => {
+                self.input_phone = phone;
+            }
+
+            BankMessage::UserMoneyChanged(money) => {
+                self.input_money = money;
+            }
+
+            BankMessage::UserCardNumChanged(card) => {
+                self.input_card_num = card;
+            }
+
+            BankMessage::CreateUser => {
+                match bank.create_user(self.input_fio.clone(), self.input_phone.clone(), self.input_money.clone()) {
+                    Ok(()) => {
+                        toasts.success(format!("Клиент {} создан", self.input_fio.trim()));
+                        self.reset_inputs()
+                    },
+                    Err(e) => toasts.error(&e)
+                }
+            }
+
+            BankMessage::DeleteUser => {
+                toasts.outcome(&bank.delete_user(self.input_card_num.clone()), "Клиент удалён");
+            }
+
+            BankMessage::UpdateUser => {
+                match bank.update_customer(self.input_card_num.clone(), self.input_fio.clone(), self.input_phone.clone()) {
+                    Ok(()) => {
+                        toasts.success("Данные клиента обновлены");
+                        self.reset_inputs()
+                    },
+                    Err(e) => toasts.error(&e)
+                }
+            }
+
+            BankMessage::SearchChanged(query) => {
+                self.input_search = query;
+                self.customers_page = 0;
+            }
+
+            BankMessage::ShowCustomersPage(page) => {
+                self.customers_page = page;
+            }
+
+            BankMessage::ToggleCardNumbers => {
+                self.show_card_numbers = !self.show_card_numbers;
+            }
+
+            BankMessage::OpenAccount => {
+                match bank.open_account(self.input_card_num.clone(), self.input_money.clone()) {
+                    Ok(card) => {
+                        toasts.success(format!("Открыта карта {}", card.masked()));
+                        self.reset_inputs()
+                    },
+                    Err(e) => toasts.error(&e)
+                }
+            }
+
+            BankMessage::CloseAccount => {
+                toasts.outcome(&bank.close_account(self.input_card_num.clone()), "Карта закрыта");
+            }
+
+            BankMessage::ImportPathChanged(path) => {
+                self.input_import_path = path;
+            }
+
+            BankMessage::ImportCustomers | BankMessage::ImportTransfers => {
+                let import = if matches!(message, BankMessage::ImportCustomers) { import_customers } else { import_transfers };
+                let path = self.input_import_path.trim().to_string();
+                match import_file(bank, Path::new(&path), import) {
+                    Ok(report) => {
+                        toasts.success(format!("Импортировано строк: {} из {}", report.imported(), report.rows.len()));
+                        self.import_report = Some(report)
+                    },
+                    Err(e) => toasts.error(&e)
+                }
+            }
+
+            BankMessage::CashCardChanged(card) => {
+                self.input_cash_card = card;
+            }
+
+            BankMessage::CashAmountChanged(amount) => {
+                self.input_cash_amount = amount;
+            }
+
+            BankMessage::Deposit => {
+                match bank.deposit(self.input_cash_card.clone(), self.input_cash_amount.clone()) {
+                    Ok(tx) => {
+                        toasts.success(format!("Зачислено {}", tx.get_amount()));
+                        self.input_cash_amount.clear()
+                    },
+                    Err(e) => toasts.error(&e)
+                }
+            }
+
+            BankMessage::Withdraw => {
+                match bank.withdraw(self.input_cash_card.clone(), self.input_cash_amount.clone()) {
+                    Ok(tx) => {
+                        toasts.success(format!("Выдано {}", tx.get_amount()));
+                        self.input_cash_amount.clear()
+                    },
+                    Err(e) => toasts.error(&e)
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn view<'a>(&'a self, bank: &'a BankState) -> Element<'a, BankMessage> {
+        let (customers, found) = bank.customers_page(&self.input_search, self.customers_page, CUSTOMERS_PER_PAGE);
+        let pages = found.div_ceil(CUSTOMERS_PER_PAGE).max(1);
+        let label = text("Welcome to User's constructor")
+            .size(50)
+            .width(Length::Fill)
+            .horizontal_alignment(alignment::Horizontal::Center);
+        let label_user = if bank.get_customers().is_empty() {
+            text("Users are empty... Try to create some Users")
+        } else {
+            text(format!("Bank have {} Users with {} cards", bank.get_customers().len(), bank.get_accounts().len()))
+        }
+            .size(30)
+            .width(Length::Fill)
+            .horizontal_alignment(alignment::Horizontal::Center);
+
+        let users_vec: Vec<Element<'_, BankMessage, Theme, Renderer>> = {
+            customers.into_iter().map(|customer| customer_view(customer, bank, self.show_card_numbers).into()).collect()
+        };
+
+        let users_container = Row::from_vec(users_vec).spacing(10);
+
+        let search = text_input("Поиск по ФИО", &self.input_search)
+            .width(400)
+            .padding(10)
+            .size(15)
+            .on_input(BankMessage::SearchChanged);
+
+        let page = self.customers_page;
+        let pager = row![
+            button(text("<-")).padding(10).on_press_maybe((page > 0).then(|| BankMessage::ShowCustomersPage(page.min(pages) - 1))),
+            text(format!("Страница {} из {pages}", (page + 1).min(pages))).size(15),
+            button(text("->")).padding(10).on_press_maybe((page + 1 < pages).then_some(BankMessage::ShowCustomersPage(page + 1)))
+        ].spacing(10).align_items(Alignment::Center);
+
+        let scrollable = Scrollable::new(users_container)
+            .width(Length::Fill)
+            .height(Length::Shrink)
+            .direction(Direction::Horizontal(Properties::new()));
+
+        let input = |text, input| {
+            text_input(text, input)
+                .width(300)
+                .padding(15)
+                .size(15)
+        };
+
+        let (fio, phone, money, card) = (
+            validation::fio(&self.input_fio),
+            validation::phone(&self.input_phone),
+            validation::money(&self.input_money),
+            validation::card(&self.input_card_num)
+        );
+        let new_customer_valid = fio.is_valid() && phone.is_valid() && money.is_valid();
+        let card_valid = card.is_valid();
+        let update_valid = card_valid && fio.is_valid_or_empty() && phone.is_valid_or_empty();
+        let open_valid = card_valid && money.is_valid();
+
+        let inputs = row![
+            field(input("Write the FIO", &self.input_fio).on_input(BankMessage::UserFioChanged), fio),
+            field(input("Write the Phone number", &self.input_phone).on_input(BankMessage::UserPhoneNumChanged), phone),
+            field(input("Write the Initial money", &self.input_money).on_input(BankMessage::UserMoneyChanged), money),
+        ].spacing(10);
+
+        let create_user_btn = button(text("Create User"))
+            .padding(20)
+            .on_press_maybe(new_customer_valid.then_some(BankMessage::CreateUser));
+
+        let show_cards_btn = button(text(if self.show_card_numbers { "Скрыть номера карт" } else { "Показать номера карт" }))
+            .padding(20)
+            .on_press(BankMessage::ToggleCardNumbers);
+
+        let button_row = row![create_user_btn, show_cards_btn]
+            .spacing(10)
+            .align_items(Alignment::Center);
+
+        let deleted_interface = row![
+            field(input("Write the Card", &self.input_card_num).on_input(BankMessage::UserCardNumChanged), card),
+            button(text("Delete User"))
+                .padding(20)
+                .on_press_maybe(card_valid.then_some(BankMessage::DeleteUser)),
+            button(text("Update User"))
+                .padding(20)
+                .on_press_maybe(update_valid.then_some(BankMessage::UpdateUser)),
+            button(text("Open Card"))
+                .padding(20)
+                .on_press_maybe(open_valid.then_some(BankMessage::OpenAccount)),
+            button(text("Close Card"))
+                .padding(20)
+                .on_press_maybe(card_valid.then_some(BankMessage::CloseAccount))
+        ].align_items(Alignment::Center).spacing(10);
+
+        let (cash_card, cash_amount) = (validation::card(&self.input_cash_card), validation::amount(&self.input_cash_amount));
+        let cash_valid = cash_card.is_valid() && cash_amount.is_valid();
+        let cash_interface = row![
+            field(input("Card for cash", &self.input_cash_card).on_input(BankMessage::CashCardChanged), cash_card),
+            field(
+                text_input("Amount", &self.input_cash_amount)
+                    .width(150)
+                    .padding(15)
+                    .size(15)
+                    .on_input(BankMessage::CashAmountChanged),
+                cash_amount
+            ),
+            button(text("Пополнить"))
+                .padding(20)
+                .on_press_maybe(cash_valid.then_some(BankMessage::Deposit)),
+            button(text("Снять"))
+                .padding(20)
+                .on_press_maybe(cash_valid.then_some(BankMessage::Withdraw))
+        ].align_items(Alignment::Center).spacing(10);
+
+        let import_interface = row![
+            input("CSV file (customers.csv)", &self.input_import_path).on_input(BankMessage::ImportPathChanged),
+            button(text("Import users"))
+                .padding(20)
+                .on_press(BankMessage::ImportCustomers),
+            button(text("Import transfers"))
+                .padding(20)
+                .on_press(BankMessage::ImportTransfers)
+        ].align_items(Alignment::Center).spacing(10);
+
+        //Per-row outcome of the last import: how many rows went in and why the others did not
+        let import_report = self.import_report.as_ref().map_or(column![], |report| {
+            report.failed().fold(
+                column![text(format!("Импортировано строк: {} из {}", report.imported(), report.rows.len())).size(15)],
+                |lines, (line, e)| lines.push(text(format!("Строка {line}: {e}")).size(12).style(Color::from_rgb8(237, 135, 150)))
+            ).spacing(5)
+        });
+
+        container(column![Space::with_height(15), label, label_user, search, pager, scrollable, inputs, button_row, deleted_interface, cash_interface, import_interface, import_report].align_items(Alignment::Center).spacing(35)).align_y(alignment::Vertical::Center).into()
+    }
+}
+
+fn customer_view<'a>(customer: &'a Customer, bank: &'a BankState, show_card_numbers: bool) -> Container<'a, BankMessage, Theme, Renderer> {
+    let fio = customer.check_fio();
+    let phone_number = customer.check_phone();
+
+    let text_fio = text(format!("Ф.И.О: {fio}")).size(12);
+    let text_phone = text(format!("Телефон: {phone_number}")).size(12);
+
+    let cards = bank.get_customer_accounts(customer)
+        .fold(column![text_fio, text_phone].align_items(Alignment::Start).spacing(10), |cards, account| {
+            cards.push(account_view(account, show_card_numbers))
+        });
+
+    container(cards)
+        .center_y()
+        .width(190)
+        .padding(10)
+        .style(iced::theme::Container::Custom(Box::new(ContainerStyle)))
+}
+
+fn account_view(account: &Account, show_card_number: bool) -> Container<'_, BankMessage, Theme, Renderer> {
+    let card_number = if show_card_number {
+        account.check_card_number().to_string()
+    } else {
+        account.check_card_number().masked()
+    };
+    let balance = account.check_balance();
+    let other_balances = account.get_balances()
+        .filter(|other| other.currency() != balance.currency())
+        .map(|other| other.to_string())
+        .collect::<Vec<_>>();
+
+    let text_card_num = text(format!("Номер карты: {card_number}")).size(12);
+    let text_balance = text(format!("Баланс: {balance}")).size(12);
+
+    let mut details = column![text_card_num, text_balance].align_items(Alignment::Start).spacing(5);
+    if !other_balances.is_empty() {
+        details = details.push(text(format!("Другие валюты: {}", other_balances.join(", "))).size(12));
+    }
+
+    container(details)
+        .width(Length::Fill)
+        .padding(5)
+        .style(iced::theme::Container::Custom(Box::new(ContainerStyle)))
+}
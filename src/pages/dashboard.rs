@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+use iced::{Alignment, Color, Element, Length};
+use iced::alignment::Horizontal;
+use iced::widget::{button, column, container, text, Space};
+use bank_model::{BankErrors, BankState, TransactionStatus};
+use bank_model::money::{Currency, Money};
+use crate::BankMessage;
+use crate::router::{Page, Route};
+use crate::toast::Toasts;
+
+//Overview of the whole bank, nothing to type here. The figures walk every card, transaction
+//and posting, so they are worked out once per operation instead of on every redraw
+#[derive(Debug, Default)]
+pub struct DashboardPage {
+    //Minor units kept on all cards, per currency. Added up in u128 like the trial balance, so no card is left out
+    totals: BTreeMap<Currency, u128>,
+    failed: usize,
+    //What the last audit found wrong with the books
+    books: Option<BankErrors>
+}
+
+impl Page for DashboardPage {
+    fn update(&mut self, _bank: &mut BankState, _toasts: &mut Toasts, _message: BankMessage) {}
+
+    fn bank_changed(&mut self, bank: &BankState, books: &Result<(), BankErrors>) {
+        self.totals.clear();
+        for money in bank.get_accounts().values().flat_map(|account| account.get_balances()) {
+            *self.totals.entry(money.currency()).or_default() += money.minor_units() as u128;
+        }
+        self.failed = bank.get_transactions().iter().filter(|tx| tx.get_status() == TransactionStatus::Failed).count();
+        self.books = books.clone().err();
+    }
+
+    fn view<'a>(&'a self, bank: &'a BankState) -> Element<'a, BankMessage> {
+        let bank_label = text(format!("Bank {}", bank.name))
+            .size(50)
+            .width(Length::Fill)
+            .horizontal_alignment(Horizontal::Center);
+
+        if bank.get_customers().is_empty() {
+            return container(
+                column![
+                    Space::with_height(15),
+                    bank_label,
+                    Space::with_height(30),
+                    text("Users are empty...").size(35),
+                    button(text("Try to create some Users"))
+                        .padding(20)
+                        .on_press(BankMessage::Navigate(Route::Customers))
+                ].spacing(35).align_items(Alignment::Center).width(Length::Fill)
+            ).align_x(Horizontal::Center).into()
+        }
+
+        let failed = self.failed;
+        let books_status = match &self.books {
+            None => text("Баланс сведён").size(20).style(Color::from_rgb8(166, 218, 149)),
+            Some(e) => text(e.to_string()).size(20).style(Color::from_rgb8(237, 135, 150))
+        };
+
+        let stats = column![
+            text(format!("Клиентов: {}", bank.get_customers().len())).size(25),
+            text(format!("Карт: {}", bank.get_accounts().len())).size(25),
+            text(format!("Операций: {} (отклонено {failed})", bank.get_transactions().len())).size(25),
+        ].spacing(10).align_items(Alignment::Center);
+        let money = self.totals.iter()
+            .fold(column![text("Деньги на картах").size(25)].spacing(5), |money, (&currency, &units)| money.push(
+                match u64::try_from(units) {
+                    Ok(units) => text(Money::new(units, currency).to_string()).size(20),
+                    Err(_) => text(format!("Сумма в {currency} не помещается в счёт")).size(20).style(Color::from_rgb8(237, 135, 150))
+                }
+            ))
+            .align_items(Alignment::Center);
+
+        container(
+            column![
+                Space::with_height(15),
+                bank_label,
+                Space::with_height(30),
+                stats,
+                money,
+                books_status
+            ].spacing(25).align_items(Alignment::Center).width(Length::Fill)
+        ).align_x(Horizontal::Center).into()
+    }
+}
@@ -0,0 +1,209 @@
+use iced::{Alignment, Color, Element, Length, Renderer, Theme};
+use iced::alignment::Horizontal;
+use iced::theme::Button;
+use iced::widget::{button, column, container, row, text, text_input, Container, Scrollable, Space};
+use bank_model::{BankAccount, BankErrors, BankState, Transaction};
+use bank_model::card::CardNumber;
+use bank_model::money::Money;
+use bank_model::statement::{self, StatementFilter, StatementLine, statement};
+use crate::BankMessage;
+use crate::router::Page;
+use crate::styles::ContainerStyle;
+use crate::toast::Toasts;
+use crate::validation::{self, field};
+use super::{kind_label, recipient_label, sender_label};
+
+//Only the newest lines are drawn, the CSV export on the reports page has all of them
+const SHOWN_LINES: usize = 50;
+
+//Statement of one card
+#[derive(Debug, Default)]
+pub struct HistoryPage {
+    card_input: String,
+    from_input: String,
+    to_input: String,
+    min_amount_input: String,
+    max_amount_input: String,
+    direction: Option<statement::Direction>,
+    //Walking the whole bank for it takes a while, so it is redone only when the inputs or the bank change.
+    //None while no card is typed in
+    statement: Option<Result<Statement, BankErrors>>
+}
+
+#[derive(Debug)]
+struct Statement {
+    card: CardNumber,
+    balance: Money,
+    //Lines after filtering, of which `lines` are the newest
+    total: usize,
+    lines: Vec<ShownLine>
+}
+
+//`StatementLine` borrows the bank, this one outlives the redraw
+#[derive(Debug)]
+struct ShownLine {
+    tx: Transaction,
+    direction: statement::Direction,
+    amount: Money,
+    balance_after: Money
+}
+
+impl HistoryPage {
+    fn rebuild(&mut self, bank: &BankState) {
+        if self.card_input.trim().is_empty() {
+            self.statement = None;
+            return
+        }
+        let account = self.card_input.trim().parse::<CardNumber>()
+            .and_then(|card| bank.get_accounts().get(&card)
+                .ok_or(BankErrors::CantFindUserByCard(format!("Cant find user with card number {card}"))));
+        self.statement = Some(account.and_then(|account| {
+            let filter = StatementFilter::parse(
+                &self.from_input,
+                &self.to_input,
+                self.direction,
+                &self.min_amount_input,
+                &self.max_amount_input,
+                account.check_currency()
+            )?;
+            let lines = statement(bank, account.check_card_number(), &filter);
+            Ok(Statement {
+                card: account.check_card_number(),
+                balance: account.check_balance(),
+                total: lines.len(),
+                lines: lines.iter().rev().take(SHOWN_LINES).map(|line| ShownLine {
+                    tx: line.tx.clone(),
+                    direction: line.direction,
+                    amount: line.amount,
+                    balance_after: line.balance_after
+                }).collect()
+            })
+        }));
+    }
+}
+
+impl Page for HistoryPage {
+    fn update(&mut self, bank: &mut BankState, _toasts: &mut Toasts, message: BankMessage) {
+        match message {
+            BankMessage::StatementCardChanged(card) => {
+                self.card_input = card;
+            }
+
+            BankMessage::StatementFromChanged(date) => {
+                self.from_input = date;
+            }
+
+            BankMessage::StatementToChanged(date) => {
+                self.to_input = date;
+            }
+
+            BankMessage::StatementMinChanged(amount) => {
+                self.min_amount_input = amount;
+            }
+
+            BankMessage::StatementMaxChanged(amount) => {
+                self.max_amount_input = amount;
+            }
+
+            BankMessage::StatementDirection(direction) => {
+                self.direction = direction;
+            }
+
+            _ => return
+        }
+        //Filters still apply while typing
+        self.rebuild(bank);
+    }
+
+    fn bank_changed(&mut self, bank: &BankState, _books: &Result<(), BankErrors>) {
+        self.rebuild(bank);
+    }
+
+    fn view<'a>(&'a self, _bank: &'a BankState) -> Element<'a, BankMessage> {
+        let intro_text = text("Выписка по карте")
+            .size(50)
+            .width(Length::Fill)
+            .horizontal_alignment(Horizontal::Center);
+
+        let input = |text, input, width| {
+            text_input(text, input)
+                .width(width)
+                .padding(10)
+                .size(15)
+        };
+
+        let card_input = field(
+            input("Card number...", &self.card_input, 300).on_input(BankMessage::StatementCardChanged),
+            validation::card(&self.card_input)
+        );
+
+        let filters = row![
+            field(input("From (2024-01-31)", &self.from_input, 160).on_input(BankMessage::StatementFromChanged), validation::date(&self.from_input)),
+            field(input("To (2024-12-31)", &self.to_input, 160).on_input(BankMessage::StatementToChanged), validation::date(&self.to_input)),
+            field(input("Min amount", &self.min_amount_input, 120).on_input(BankMessage::StatementMinChanged), validation::money(&self.min_amount_input)),
+            field(input("Max amount", &self.max_amount_input, 120).on_input(BankMessage::StatementMaxChanged), validation::money(&self.max_amount_input)),
+        ].spacing(5);
+
+        let direction_btn = |label, direction| {
+            button(text(label))
+                .padding(10)
+                .style(if self.direction == direction { Button::Primary } else { Button::Secondary })
+                .on_press(BankMessage::StatementDirection(direction))
+        };
+        let directions = row![
+            direction_btn("Все", None),
+            direction_btn("Входящие", Some(statement::Direction::Incoming)),
+            direction_btn("Исходящие", Some(statement::Direction::Outgoing))
+        ].spacing(5);
+
+        let body: Element<'_, BankMessage, Theme, Renderer> = match &self.statement {
+            Some(Ok(shown)) => {
+                let header = text(format!("{}: {} операций, показаны последние {}, баланс {}", shown.card.masked(), shown.total, shown.lines.len(), shown.balance))
+                    .size(15);
+                let list = column(shown.lines.iter().map(|line| statement_line_view(&StatementLine {
+                    tx: &line.tx,
+                    direction: line.direction,
+                    amount: line.amount,
+                    balance_after: line.balance_after
+                }).into())).spacing(5);
+                column![
+                    header,
+                    Scrollable::new(list).height(350).width(Length::Fill)
+                ].spacing(10).align_items(Alignment::Center).into()
+            },
+            Some(Err(e)) => text(e.to_string()).size(15).style(Color::from_rgb8(237, 135, 150)).into(),
+            None => text("").into()
+        };
+
+        container(
+            column![
+                intro_text,
+                Space::with_height(20),
+                card_input,
+                filters,
+                directions,
+                body
+            ].spacing(15).align_items(Alignment::Center).width(Length::Fill)
+        ).align_x(Horizontal::Center).into()
+    }
+}
+
+fn statement_line_view<'a>(line: &StatementLine<'a>) -> Container<'a, BankMessage, Theme, Renderer> {
+    let tx = line.tx;
+    let (sign, counterpart, color) = match line.direction {
+        statement::Direction::Incoming => ("+", sender_label(tx), Color::from_rgb8(166, 218, 149)),
+        statement::Direction::Outgoing => ("-", recipient_label(tx), Color::from_rgb8(237, 135, 150))
+    };
+
+    container(
+        row![
+            text(tx.get_tx_time().format("%Y-%m-%d %H:%M").to_string()).size(12).width(120),
+            text(format!("№{} {}", tx.get_id(), kind_label(tx))).size(12).width(140),
+            text(counterpart).size(12).width(220),
+            text(format!("{sign}{}", line.amount)).size(12).width(140).style(color),
+            text(format!("Баланс: {}", line.balance_after)).size(12).width(180)
+        ].spacing(10).align_items(Alignment::Center)
+    )
+        .padding(5)
+        .style(iced::theme::Container::Custom(Box::new(ContainerStyle)))
+}
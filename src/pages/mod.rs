@@ -0,0 +1,44 @@
+use bank_model::{Recipient, Transaction, TransactionKind};
+use crate::router::{Page, Route};
+
+mod customers;
+mod dashboard;
+mod history;
+mod reports;
+mod settings;
+mod transfers;
+
+//Every page of the app in sidebar order, a new page is added here and to `Route`
+pub fn registry() -> Vec<(Route, Box<dyn Page>)> {
+    vec![
+        (Route::Dashboard, Box::<dashboard::DashboardPage>::default()),
+        (Route::Customers, Box::<customers::CustomersPage>::default()),
+        (Route::Transfers, Box::<transfers::TransfersPage>::default()),
+        (Route::History, Box::<history::HistoryPage>::default()),
+        (Route::Reports, Box::<reports::ReportsPage>::default()),
+        (Route::Settings, Box::<settings::SettingsPage>::default()),
+    ]
+}
+
+fn sender_label(tx: &Transaction) -> String {
+    tx.get_sender_card().map_or("наличные".to_string(), |card| card.masked())
+}
+
+fn recipient_label(tx: &Transaction) -> String {
+    match (tx.get_recipient(), tx.get_recipient_card()) {
+        (Recipient::Phone(phone), Some(card)) => format!("{phone} ({})", card.masked()),
+        (Recipient::Phone(phone), None) => phone.to_string(),
+        (Recipient::Card(card), _) => card.masked(),
+        (Recipient::Internal(card), _) => format!("своя карта {}", card.masked()),
+        (Recipient::Cash, _) => "наличные".to_string()
+    }
+}
+
+fn kind_label(tx: &Transaction) -> &'static str {
+    match tx.get_kind() {
+        TransactionKind::Transfer => "Перевод",
+        TransactionKind::Deposit => "Пополнение",
+        TransactionKind::Withdrawal => "Снятие",
+        TransactionKind::Fee => "Комиссия"
+    }
+}
@@ -0,0 +1,112 @@
+use std::path::Path;
+use iced::{Alignment, Color, Element, Length};
+use iced::alignment::Horizontal;
+use iced::widget::{button, column, container, row, text, text_input, Space};
+use std::collections::BTreeMap;
+use bank_model::{BankErrors, BankState};
+use bank_model::export::{EXPORT_FILE, ExportScope, export_transactions};
+use bank_model::ledger::Totals;
+use bank_model::money::{Currency, Money};
+use crate::BankMessage;
+use crate::router::Page;
+use crate::toast::Toasts;
+use crate::validation::{self, field};
+
+//The books behind the balances and the CSV export
+#[derive(Debug, Default)]
+pub struct ReportsPage {
+    export_card_input: String,
+    export_from_input: String,
+    export_to_input: String,
+    //The books as of the last operation, auditing them means walking every posting
+    postings: usize,
+    trial_balance: BTreeMap<Currency, Totals>,
+    books: Option<BankErrors>
+}
+
+impl Page for ReportsPage {
+    fn update(&mut self, bank: &mut BankState, toasts: &mut Toasts, message: BankMessage) {
+        match message {
+            BankMessage::ExportCardChanged(card) => {
+                self.export_card_input = card;
+            }
+
+            BankMessage::ExportFromChanged(date) => {
+                self.export_from_input = date;
+            }
+
+            BankMessage::ExportToChanged(date) => {
+                self.export_to_input = date;
+            }
+
+            BankMessage::ExportCsv => {
+                let exported = ExportScope::parse(&self.export_card_input, &self.export_from_input, &self.export_to_input)
+                    .and_then(|scope| export_transactions(bank, &scope, Path::new(EXPORT_FILE)));
+                match exported {
+                    Ok(rows) => toasts.success(format!("Выгружено {rows} операций в {EXPORT_FILE}")),
+                    Err(e) => toasts.error(&e)
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn bank_changed(&mut self, bank: &BankState, books: &Result<(), BankErrors>) {
+        self.postings = bank.get_ledger().get_postings().len();
+        self.trial_balance = bank.get_ledger().trial_balance();
+        self.books = books.clone().err();
+    }
+
+    fn view<'a>(&'a self, _bank: &'a BankState) -> Element<'a, BankMessage> {
+        let intro_text = text("Отчёты")
+            .size(50)
+            .width(Length::Fill)
+            .horizontal_alignment(Horizontal::Center);
+
+        let books_status = match &self.books {
+            None => text("Баланс сведён").size(15).style(Color::from_rgb8(166, 218, 149)),
+            Some(e) => text(e.to_string()).size(15).style(Color::from_rgb8(237, 135, 150))
+        };
+        let books = self.trial_balance.iter()
+            .fold(column![text(format!("Проводок: {}", self.postings)).size(15), books_status].spacing(5), |books, (&currency, totals)| {
+                let money = |units: u128| Money::new(u64::try_from(units).unwrap_or(u64::MAX), currency);
+                books.push(text(format!("Дебет {} / Кредит {}", money(totals.debits), money(totals.credits))).size(15))
+            })
+            .align_items(Alignment::Center);
+
+        let small_input = |text, input, width| {
+            text_input(text, input)
+                .width(width)
+                .padding(10)
+                .size(15)
+        };
+
+        //Every field may stay empty: the whole bank over all time
+        let (card, from, to) = (
+            validation::card(&self.export_card_input),
+            validation::date(&self.export_from_input),
+            validation::date(&self.export_to_input)
+        );
+        let export_valid = card.is_valid_or_empty() && from.is_valid_or_empty() && to.is_valid_or_empty();
+        let export_interface = row![
+            field(small_input("Card (all)", &self.export_card_input, 200).on_input(BankMessage::ExportCardChanged), card),
+            field(small_input("From (2024-01-31)", &self.export_from_input, 150).on_input(BankMessage::ExportFromChanged), from),
+            field(small_input("To (2024-12-31)", &self.export_to_input, 150).on_input(BankMessage::ExportToChanged), to),
+            button("Экспорт CSV")
+                .padding(10)
+                .on_press_maybe(export_valid.then_some(BankMessage::ExportCsv))
+        ].spacing(5).align_items(Alignment::Start);
+
+        container(
+            column![
+                Space::with_height(20),
+                intro_text,
+                Space::with_height(40),
+                books,
+                Space::with_height(40),
+                export_interface
+            ].spacing(10).align_items(Alignment::Center)
+        ).align_x(Horizontal::Center).into()
+    }
+}
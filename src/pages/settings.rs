@@ -0,0 +1,115 @@
+use iced::{Alignment, Element, Length};
+use iced::alignment::Horizontal;
+use iced::widget::{button, column, container, row, text, text_input, Space};
+use bank_model::BankState;
+use crate::BankMessage;
+use crate::router::Page;
+use crate::toast::Toasts;
+use crate::validation::{self, field};
+
+//Card BIN and exchange rates
+#[derive(Debug, Default)]
+pub struct SettingsPage {
+    bin_input: String,
+    rate_from_input: String,
+    rate_to_input: String,
+    rate_value_input: String
+}
+
+impl Page for SettingsPage {
+    fn update(&mut self, bank: &mut BankState, toasts: &mut Toasts, message: BankMessage) {
+        match message {
+            BankMessage::CardBinChanged(bin) => {
+                self.bin_input = bin;
+            }
+
+            BankMessage::SetCardBin => {
+                match bank.set_card_bin(self.bin_input.clone()) {
+                    Ok(()) => {
+                        toasts.success(format!("Новые карты начинаются с {}", bank.get_card_bin()));
+                        self.bin_input.clear()
+                    },
+                    Err(e) => toasts.error(&e)
+                }
+            }
+
+            BankMessage::RateFromChanged(currency) => {
+                self.rate_from_input = currency;
+            }
+
+            BankMessage::RateToChanged(currency) => {
+                self.rate_to_input = currency;
+            }
+
+            BankMessage::RateValueChanged(rate) => {
+                self.rate_value_input = rate;
+            }
+
+            BankMessage::SetExchangeRate => {
+                match bank.set_exchange_rate(self.rate_from_input.clone(), self.rate_to_input.clone(), self.rate_value_input.clone()) {
+                    Ok(()) => {
+                        toasts.success("Курс установлен");
+                        self.rate_value_input.clear()
+                    },
+                    Err(e) => toasts.error(&e)
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn view<'a>(&'a self, bank: &'a BankState) -> Element<'a, BankMessage> {
+        let intro_text = text("Настройки")
+            .size(50)
+            .width(Length::Fill)
+            .horizontal_alignment(Horizontal::Center);
+
+        let small_input = |text, input| {
+            text_input(text, input)
+                .width(100)
+                .padding(10)
+                .size(15)
+        };
+
+        let bin = validation::bin(&self.bin_input);
+        let bin_valid = bin.is_valid();
+        let bin_interface = row![
+            text(format!("BIN: {}", bank.get_card_bin())).size(15),
+            field(small_input("New BIN", &self.bin_input).width(150).on_input(BankMessage::CardBinChanged), bin),
+            button(text("Set BIN"))
+                .padding(10)
+                .on_press_maybe(bin_valid.then_some(BankMessage::SetCardBin))
+        ].align_items(Alignment::Center).spacing(10);
+
+        let rates_list = column(
+            bank.get_exchange_rates().get_rates().iter()
+                .map(|rate| text(rate.to_string()).size(12).into())
+        ).spacing(5);
+
+        let (from, to, rate) = (
+            validation::currency(&self.rate_from_input),
+            validation::currency(&self.rate_to_input),
+            validation::rate(&self.rate_value_input)
+        );
+        let rate_valid = from.is_valid() && to.is_valid() && rate.is_valid();
+        let rates_interface = row![
+            field(small_input("From (USD)", &self.rate_from_input).on_input(BankMessage::RateFromChanged), from),
+            field(small_input("To (RUB)", &self.rate_to_input).on_input(BankMessage::RateToChanged), to),
+            field(small_input("Rate", &self.rate_value_input).on_input(BankMessage::RateValueChanged), rate),
+            button("Установить курс")
+                .padding(10)
+                .on_press_maybe(rate_valid.then_some(BankMessage::SetExchangeRate))
+        ].spacing(5).align_items(Alignment::Start);
+
+        container(
+            column![
+                Space::with_height(20),
+                intro_text,
+                Space::with_height(40),
+                bin_interface,
+                row![rates_list, rates_interface].spacing(30).align_items(Alignment::Center)
+            ].spacing(20).align_items(Alignment::Center)
+        ).align_x(Horizontal::Center).into()
+    }
+}
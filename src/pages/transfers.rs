@@ -0,0 +1,231 @@
+use iced::{Alignment, Color, Element, Length, Renderer, Theme};
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::{button, column, container, row, text, text_input, Container, Row, Scrollable, Space};
+use iced::widget::scrollable::{Direction, Properties};
+use bank_model::{BankErrors, BankState, Transaction, TransactionStatus};
+use crate::BankMessage;
+use crate::router::Page;
+use crate::styles::ContainerStyle;
+use crate::toast::Toasts;
+use crate::validation::{self, field};
+use super::{kind_label, recipient_label, sender_label};
+
+//Only the newest transactions are drawn, the full history is on the statement page
+const RECENT_TRANSACTIONS: usize = 20;
+
+#[derive(Debug, Default)]
+pub struct TransfersPage {
+    sender_card_input: String,
+    amount_input: String,
+    recipient_card_input: String,
+    recipient_phone_input: String,
+    transfer_mode: TransferMode
+}
+impl TransfersPage {
+    fn reset_inputs(&mut self) {
+        self.sender_card_input.clear();
+        self.amount_input.clear();
+        self.recipient_card_input.clear();
+        self.recipient_phone_input.clear();
+    }
+    //Inputs are kept after a failure, so the transfer can be fixed and sent again
+    fn finish(&mut self, toasts: &mut Toasts, result: Result<Transaction, BankErrors>) {
+        match result {
+            Ok(tx) => {
+                toasts.success(format!("Перевод №{} на {} выполнен", tx.get_id(), tx.get_amount()));
+                self.reset_inputs()
+            },
+            Err(e) => toasts.error(&e)
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+enum TransferMode {
+    #[default]
+    TransferByPhone,
+    TransferByCard,
+    BetweenOwnCards
+}
+
+impl Page for TransfersPage {
+    fn update(&mut self, bank: &mut BankState, toasts: &mut Toasts, message: BankMessage) {
+        match message {
+            BankMessage::ByPhoneMode => {
+                self.transfer_mode = TransferMode::TransferByPhone
+            }
+
+            BankMessage::ByCardMode => {
+                self.transfer_mode = TransferMode::TransferByCard
+            }
+
+            BankMessage::ByOwnCardsMode => {
+                self.transfer_mode = TransferMode::BetweenOwnCards
+            }
+
+            BankMessage::FromUserChanged(card) => {
+                self.sender_card_input = card;
+            }
+
+            BankMessage::ToUserCardChanged(card) => {
+                self.recipient_card_input = card;
+            }
+
+            BankMessage::ToUserPhoneChanged(phone_num) => {
+                self.recipient_phone_input = phone_num;
+            }
+
+            BankMessage::TransferAmountChanged(amount) => {
+                self.amount_input = amount;
+            }
+
+            BankMessage::TransferByPhone => {
+                let result = bank.transfer_by_phone(self.sender_card_input.clone(), self.recipient_phone_input.clone(), self.amount_input.clone());
+                self.finish(toasts, result)
+            }
+
+            BankMessage::TransferByCard => {
+                let result = bank.transfer_by_card(self.sender_card_input.clone(), self.recipient_card_input.clone(), self.amount_input.clone());
+                self.finish(toasts, result)
+            }
+
+            BankMessage::TransferBetweenOwnCards => {
+                let result = bank.transfer_between_own_accounts(self.sender_card_input.clone(), self.recipient_card_input.clone(), self.amount_input.clone());
+                self.finish(toasts, result)
+            }
+
+            BankMessage::ReverseTransaction(id) => {
+                toasts.outcome(&bank.reverse_transaction(id), format!("Перевод №{id} отменён"));
+            }
+
+            _ => {}
+        }
+    }
+
+    fn view<'a>(&'a self, bank: &'a BankState) -> Element<'a, BankMessage> {
+        let transactions = bank.get_transactions();
+
+        let intro_text = text("Welcome to Transfers")
+            .size(50)
+            .width(Length::Fill)
+            .horizontal_alignment(Horizontal::Center);
+
+        let phone_mode_btn = button("По номеру телефона")
+            .padding(20)
+            .on_press(BankMessage::ByPhoneMode);
+
+        let card_mode_btn = button("По номеру карты")
+            .padding(20)
+            .on_press(BankMessage::ByCardMode);
+
+        let own_cards_mode_btn = button("Между своими картами")
+            .padding(20)
+            .on_press(BankMessage::ByOwnCardsMode);
+
+        let input = |text, input| {
+            text_input(text, input)
+                .width(300)
+                .padding(15)
+                .size(15)
+        };
+
+        let (recipient_input, recipient, transfer_btn, message) = match self.transfer_mode {
+            TransferMode::TransferByPhone => (
+                input("User to (Phone number)...", &self.recipient_phone_input).on_input(BankMessage::ToUserPhoneChanged),
+                validation::phone(&self.recipient_phone_input),
+                button("Перевод по номеру").padding(20),
+                BankMessage::TransferByPhone
+            ),
+            TransferMode::TransferByCard => (
+                input("User to (Card number)...", &self.recipient_card_input).on_input(BankMessage::ToUserCardChanged),
+                validation::card(&self.recipient_card_input),
+                button("Перевод по карте").padding(20),
+                BankMessage::TransferByCard
+            ),
+            TransferMode::BetweenOwnCards => (
+                input("To own card...", &self.recipient_card_input).on_input(BankMessage::ToUserCardChanged),
+                validation::card(&self.recipient_card_input),
+                button("Перевод между картами").padding(20),
+                BankMessage::TransferBetweenOwnCards
+            )
+        };
+        let sender_placeholder = if matches!(self.transfer_mode, TransferMode::BetweenOwnCards) { "From card..." } else { "User from..." };
+        let (sender, amount) = (validation::card(&self.sender_card_input), validation::amount(&self.amount_input));
+        let ready = sender.is_valid() && recipient.is_valid() && amount.is_valid();
+
+        let transfer_form = column![
+            field(input(sender_placeholder, &self.sender_card_input).on_input(BankMessage::FromUserChanged), sender),
+            field(recipient_input, recipient),
+            field(input("Amount to send...", &self.amount_input).on_input(BankMessage::TransferAmountChanged), amount),
+            transfer_btn.on_press_maybe(ready.then_some(message))
+        ].spacing(20);
+
+        let tx_vec: Vec<Element<'_, BankMessage, Theme, Renderer>> = {
+            transactions.iter().rev().take(RECENT_TRANSACTIONS).map(|tx| transaction_view(tx).into()).collect()
+        };
+        let recent_label = text(format!("Последние {} из {} операций", tx_vec.len(), transactions.len())).size(15);
+
+        let tx_row = Row::from_vec(tx_vec).spacing(10);
+
+        let scrollbar = Scrollable::new(tx_row)
+            .width(Length::Fill)
+            .height(Length::Shrink)
+            .direction(Direction::Horizontal(Properties::new()));
+
+        container(
+            column![
+                Space::with_height(20),
+                intro_text,
+                Space::with_height(40),
+                row![card_mode_btn, phone_mode_btn, own_cards_mode_btn].spacing(5),
+                transfer_form,
+                recent_label,
+                scrollbar
+            ].spacing(10).align_items(Alignment::Center)
+        ).align_y(Vertical::Center).align_x(Horizontal::Center).into()
+    }
+}
+
+fn transaction_view(tx: &Transaction) -> Container<'_, BankMessage, Theme, Renderer> {
+    let date = tx.get_tx_time();
+    let amount = tx.get_amount();
+    let sender = sender_label(tx);
+    let recipient = recipient_label(tx);
+    let credited = tx.get_credited_amount();
+    let kind = kind_label(tx);
+    let (status, status_color) = match tx.get_status() {
+        TransactionStatus::Pending => ("В обработке", Color::from_rgb8(238, 212, 159)),
+        TransactionStatus::Completed => ("Выполнена", Color::from_rgb8(166, 218, 149)),
+        TransactionStatus::Failed => ("Отклонена", Color::from_rgb8(237, 135, 150)),
+        TransactionStatus::Reversed => ("Отменена", Color::from_rgb8(183, 189, 248))
+    };
+
+    let text_id = text(format!("№{} {kind}", tx.get_id())).size(12);
+    let text_status = text(format!("Статус: {status}")).size(12).style(status_color);
+    let text_date = text(format!("Дата: {date}")).size(12);
+    let text_amount = text(format!("Сумма: {amount}")).size(12);
+    let text_sender = text(format!("Отправитель: {sender}")).size(12);
+    let text_recipient = text(format!("Получатель: {recipient}")).size(12);
+    let mut details = column![text_id, text_status, text_date, text_amount, text_sender, text_recipient].align_items(Alignment::Start).spacing(10);
+    if let Some(rate) = tx.get_exchange_rate() {
+        details = details.push(text(format!("Зачислено: {credited} ({rate})")).size(12));
+    }
+    if let Some(original) = tx.get_reverses() {
+        details = details.push(text(format!("Возврат по №{original}")).size(12));
+    }
+    if let Some(refund) = tx.get_reversed_by() {
+        details = details.push(text(format!("Возвращена: №{refund}")).size(12));
+    }
+    if tx.is_reversible() {
+        details = details.push(
+            button(text("Reverse").size(12))
+                .padding(5)
+                .on_press(BankMessage::ReverseTransaction(tx.get_id()))
+        );
+    }
+    container(details)
+        .center_y()
+        .width(170)
+        .height(270)
+        .style(iced::theme::Container::Custom(Box::new(ContainerStyle)))
+}
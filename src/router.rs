@@ -0,0 +1,145 @@
+use iced::Element;
+use bank_model::{BankErrors, BankState};
+use crate::BankMessage;
+use crate::toast::Toasts;
+
+//Every screen the sidebar can lead to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    Dashboard,
+    Customers,
+    Transfers,
+    History,
+    Reports,
+    Settings
+}
+impl Route {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Route::Dashboard => "Главная",
+            Route::Customers => "Клиенты",
+            Route::Transfers => "Переводы",
+            Route::History => "История",
+            Route::Reports => "Отчёты",
+            Route::Settings => "Настройки"
+        }
+    }
+}
+
+//One screen of the app. Pages are created once, so what was typed survives going to another page and back.
+//Messages meant for other pages simply fall through, outcomes of operations go to `toasts`
+pub trait Page: std::fmt::Debug {
+    fn update(&mut self, bank: &mut BankState, toasts: &mut Toasts, message: BankMessage);
+    fn view<'a>(&'a self, bank: &'a BankState) -> Element<'a, BankMessage>;
+    //Called once an operation went through, for pages that keep something expensive to derive from the bank.
+    //`books` is the audit of the books after it, done once for every page
+    fn bank_changed(&mut self, _bank: &BankState, _books: &Result<(), BankErrors>) {}
+}
+
+//Visited routes, like in a browser: going somewhere new forgets the forward part
+#[derive(Debug)]
+pub struct History {
+    back: Vec<Route>,
+    current: Route,
+    forward: Vec<Route>
+}
+impl History {
+    pub fn new(start: Route) -> Self {
+        Self { back: Vec::new(), current: start, forward: Vec::new() }
+    }
+    pub fn current(&self) -> Route {
+        self.current
+    }
+    pub fn go(&mut self, route: Route) {
+        if route != self.current {
+            self.back.push(std::mem::replace(&mut self.current, route));
+            self.forward.clear();
+        }
+    }
+    pub fn back(&mut self) {
+        if let Some(route) = self.back.pop() {
+            self.forward.push(std::mem::replace(&mut self.current, route));
+        }
+    }
+    pub fn forward(&mut self) {
+        if let Some(route) = self.forward.pop() {
+            self.back.push(std::mem::replace(&mut self.current, route));
+        }
+    }
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+}
+
+#[derive(Debug)]
+pub struct Router {
+    //In sidebar order
+    pages: Vec<(Route, Box<dyn Page>)>,
+    pub history: History
+}
+impl Router {
+    pub fn new(pages: Vec<(Route, Box<dyn Page>)>, start: Route) -> Self {
+        Self { pages, history: History::new(start) }
+    }
+    pub fn routes(&self) -> impl Iterator<Item = Route> + '_ {
+        self.pages.iter().map(|(route, _)| *route)
+    }
+    pub fn page(&self) -> &dyn Page {
+        let current = self.history.current();
+        self.pages.iter().find(|(route, _)| *route == current).map(|(_, page)| page.as_ref())
+            .expect("every route has a registered page")
+    }
+    pub fn page_mut(&mut self) -> &mut dyn Page {
+        let current = self.history.current();
+        self.pages.iter_mut().find(|(route, _)| *route == current).map(|(_, page)| page.as_mut())
+            .expect("every route has a registered page")
+    }
+    pub fn bank_changed(&mut self, bank: &BankState, books: &Result<(), BankErrors>) {
+        for (_, page) in &mut self.pages {
+            page.bank_changed(bank, books);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn back_and_forward_walk_the_visited_routes() {
+        let mut history = History::new(Route::Dashboard);
+        history.go(Route::Customers);
+        history.go(Route::Transfers);
+        assert!(!history.can_go_forward());
+
+        history.back();
+        assert_eq!(history.current(), Route::Customers);
+        history.back();
+        assert_eq!(history.current(), Route::Dashboard);
+        assert!(!history.can_go_back());
+        history.back();
+        assert_eq!(history.current(), Route::Dashboard);
+
+        history.forward();
+        history.forward();
+        assert_eq!(history.current(), Route::Transfers);
+        assert!(!history.can_go_forward());
+    }
+
+    #[test]
+    fn new_route_drops_the_forward_part() {
+        let mut history = History::new(Route::Dashboard);
+        history.go(Route::Customers);
+        history.back();
+        history.go(Route::Reports);
+        assert!(!history.can_go_forward());
+
+        //Clicking the page that is already open does not pile up history
+        history.go(Route::Reports);
+        history.back();
+        assert_eq!(history.current(), Route::Dashboard);
+    }
+}
@@ -1,13 +1,13 @@
 use iced::{Background, Border, Color, Shadow, Theme, Vector};
 use iced::border::Radius;
-use iced::widget::{container};
+use iced::widget::{container, text_input};
 use iced::widget::container::Appearance;
 
 pub struct ContainerStyle;
 
 impl container::StyleSheet for ContainerStyle {
     type Style = Theme;
-    fn appearance(&self, style: &Self::Style) -> Appearance {
+    fn appearance(&self, _style: &Self::Style) -> Appearance {
         Appearance {
             text_color: Some(Color::BLACK),//Color::from_rgba8(0, 51, 90, 0.8)),
             background: Some(Background::Color(Color::from_rgba8(33, 120, 186, 0.8))),
@@ -23,4 +23,44 @@ impl container::StyleSheet for ContainerStyle {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+//Same look as the theme's input, but with a red border
+pub struct InvalidInputStyle;
+
+impl InvalidInputStyle {
+    fn red_border(appearance: text_input::Appearance) -> text_input::Appearance {
+        text_input::Appearance {
+            border: Border { color: Color::from_rgb8(237, 135, 150), width: 2.0, ..appearance.border },
+            ..appearance
+        }
+    }
+}
+
+impl text_input::StyleSheet for InvalidInputStyle {
+    type Style = Theme;
+    fn active(&self, style: &Self::Style) -> text_input::Appearance {
+        Self::red_border(style.active(&Default::default()))
+    }
+    fn focused(&self, style: &Self::Style) -> text_input::Appearance {
+        Self::red_border(style.focused(&Default::default()))
+    }
+    fn hovered(&self, style: &Self::Style) -> text_input::Appearance {
+        Self::red_border(style.hovered(&Default::default()))
+    }
+    fn disabled(&self, style: &Self::Style) -> text_input::Appearance {
+        style.disabled(&Default::default())
+    }
+    fn placeholder_color(&self, style: &Self::Style) -> Color {
+        style.placeholder_color(&Default::default())
+    }
+    fn value_color(&self, style: &Self::Style) -> Color {
+        style.value_color(&Default::default())
+    }
+    fn disabled_color(&self, style: &Self::Style) -> Color {
+        style.disabled_color(&Default::default())
+    }
+    fn selection_color(&self, style: &Self::Style) -> Color {
+        style.selection_color(&Default::default())
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::{Duration, Instant};
+use iced::{Alignment, Color, Element, Length};
+use iced::widget::{button, column, container, row, text};
+use bank_model::BankErrors;
+use crate::BankMessage;
+
+//How long a toast stays on screen unless closed earlier
+pub const TOAST_LIFETIME: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastKind {
+    Success,
+    Error
+}
+
+#[derive(Debug)]
+pub struct Toast {
+    id: u64,
+    kind: ToastKind,
+    message: String,
+    shown_at: Instant
+}
+
+//Outcomes of what the user did, newest last
+#[derive(Debug, Default)]
+pub struct Toasts {
+    next_id: u64,
+    toasts: Vec<Toast>
+}
+impl Toasts {
+    pub fn success(&mut self, message: impl Into<String>) {
+        self.push(ToastKind::Success, message.into(), Instant::now())
+    }
+    pub fn error(&mut self, error: &BankErrors) {
+        self.push(ToastKind::Error, error.to_string(), Instant::now())
+    }
+    //Reports whichever way the operation went
+    pub fn outcome<T>(&mut self, result: &Result<T, BankErrors>, success: impl Into<String>) {
+        match result {
+            Ok(_) => self.success(success),
+            Err(e) => self.error(e)
+        }
+    }
+    fn push(&mut self, kind: ToastKind, message: String, shown_at: Instant) {
+        self.next_id += 1;
+        self.toasts.push(Toast { id: self.next_id, kind, message, shown_at });
+    }
+    pub fn dismiss(&mut self, id: u64) {
+        self.toasts.retain(|toast| toast.id != id);
+    }
+    pub fn expire(&mut self, now: Instant) {
+        self.toasts.retain(|toast| now.duration_since(toast.shown_at) < TOAST_LIFETIME);
+    }
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    pub fn view(&self) -> Element<'_, BankMessage> {
+        column(self.toasts.iter().map(|toast| {
+            let color = match toast.kind {
+                ToastKind::Success => Color::from_rgb8(166, 218, 149),
+                ToastKind::Error => Color::from_rgb8(237, 135, 150)
+            };
+            container(
+                row![
+                    text(&toast.message).size(15).style(color).width(Length::Fill),
+                    button(text("x").size(12)).padding(5).on_press(BankMessage::DismissToast(toast.id))
+                ].spacing(10).align_items(Alignment::Center)
+            )
+                .padding(10)
+                .style(iced::theme::Container::Box)
+                .into()
+        }))
+            .spacing(5)
+            .padding(10)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toasts_expire_after_their_lifetime() {
+        let start = Instant::now();
+        let mut toasts = Toasts::default();
+        toasts.push(ToastKind::Error, "first".to_string(), start);
+        toasts.push(ToastKind::Success, "second".to_string(), start + Duration::from_secs(3));
+
+        toasts.expire(start + TOAST_LIFETIME - Duration::from_millis(1));
+        assert_eq!(toasts.toasts.len(), 2);
+        toasts.expire(start + TOAST_LIFETIME);
+        assert_eq!(toasts.toasts.iter().map(|toast| toast.message.as_str()).collect::<Vec<_>>(), vec!["second"]);
+        toasts.expire(start + Duration::from_secs(3) + TOAST_LIFETIME);
+        assert!(toasts.is_empty());
+    }
+
+    #[test]
+    fn dismiss_removes_only_that_toast() {
+        let mut toasts = Toasts::default();
+        toasts.error(&BankErrors::InvalidAmountProblem);
+        toasts.outcome(&Ok::<(), BankErrors>(()), "Клиент создан");
+        let first = toasts.toasts[0].id;
+
+        toasts.dismiss(first);
+        assert_eq!(toasts.toasts.len(), 1);
+        assert_eq!(toasts.toasts[0].kind, ToastKind::Success);
+    }
+}
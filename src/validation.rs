@@ -0,0 +1,140 @@
+use std::str::FromStr;
+use iced::Color;
+use iced::widget::{column, text, Column, TextInput};
+use bank_model::card::{CardNumber, validate_bin};
+use bank_model::exchange::ExchangeRate;
+use bank_model::money::{Currency, Money};
+use bank_model::phone::PhoneNumber;
+use bank_model::statement::parse_date;
+use crate::BankMessage;
+use crate::styles::InvalidInputStyle;
+
+//What a field holds while it is being typed. An empty field is not marked red,
+//it only keeps the buttons that need it disabled
+#[derive(Debug, Clone, PartialEq)]
+pub enum Check {
+    Empty,
+    Valid,
+    Invalid(&'static str)
+}
+impl Check {
+    pub fn is_valid(&self) -> bool {
+        *self == Check::Valid
+    }
+
+    //For fields that may be left blank
+    pub fn is_valid_or_empty(&self) -> bool {
+        !matches!(self, Check::Invalid(_))
+    }
+}
+
+fn check(input: &str, valid: impl FnOnce(&str) -> bool, hint: &'static str) -> Check {
+    match input.trim() {
+        "" => Check::Empty,
+        input if valid(input) => Check::Valid,
+        _ => Check::Invalid(hint)
+    }
+}
+
+pub fn fio(input: &str) -> Check {
+    check(
+        input,
+        |fio| fio.chars().any(char::is_alphabetic)
+            && fio.chars().all(|c| c.is_alphabetic() || c.is_whitespace() || matches!(c, '.' | '-' | '\'')),
+        "ФИО может содержать только буквы, пробелы, точки и дефисы"
+    )
+}
+
+pub fn phone(input: &str) -> Check {
+    check(input, |phone| PhoneNumber::from_str(phone).is_ok(), "Номер телефона: +79123456789 или 8 (912) 345-67-89")
+}
+
+pub fn card(input: &str) -> Check {
+    check(input, |card| CardNumber::from_str(card).is_ok(), "Номер карты: 16 цифр с верной контрольной суммой")
+}
+
+//Initial balance, zero is allowed. The currency is only checked to be a known code
+pub fn money(input: &str) -> Check {
+    check(input, |money| Money::parse(money, Currency::default()).is_ok(), "Сумма: 100, 100.50 или 100.50 USD")
+}
+
+//Amount that is moved somewhere, so it has to be more than zero
+pub fn amount(input: &str) -> Check {
+    match money(input) {
+        Check::Valid if Money::parse(input, Currency::default()).is_ok_and(|money| money.is_zero()) =>
+            Check::Invalid("Сумма должна быть больше нуля"),
+        check => check
+    }
+}
+
+pub fn date(input: &str) -> Check {
+    check(input, |date| parse_date(date).is_ok(), "Дата в виде 2024-01-31")
+}
+
+pub fn bin(input: &str) -> Check {
+    check(input, |bin| validate_bin(bin).is_ok(), "BIN: от 6 до 8 цифр")
+}
+
+pub fn currency(input: &str) -> Check {
+    check(input, |code| Currency::from_str(code).is_ok(), "Код валюты: RUB, USD или EUR")
+}
+
+//Only the number is checked here, the pair of currencies is a field of its own
+pub fn rate(input: &str) -> Check {
+    check(input, |rate| ExchangeRate::parse(Currency::Usd, Currency::Rub, rate).is_ok(), "Курс: больше нуля, например 92.5")
+}
+
+//The input with a red border and the hint under it while the value is wrong
+pub fn field<'a>(input: TextInput<'a, BankMessage>, check: Check) -> Column<'a, BankMessage> {
+    match check {
+        Check::Invalid(hint) => column![
+            input.style(iced::theme::TextInput::Custom(Box::new(InvalidInputStyle))),
+            text(hint).size(12).style(Color::from_rgb8(237, 135, 150))
+        ].spacing(5),
+        _ => column![input]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_fields_are_not_errors() {
+        assert_eq!(fio("  "), Check::Empty);
+        assert!(!Check::Empty.is_valid());
+        assert!(Check::Empty.is_valid_or_empty());
+    }
+
+    #[test]
+    fn fields_are_checked_by_the_model_parsers() {
+        assert!(fio("Иванов Иван-Петрович").is_valid());
+        assert!(!fio("Иван 3").is_valid());
+        assert!(phone("8 (912) 345-67-89").is_valid());
+        assert!(!phone("12ab").is_valid());
+        assert!(card("4111 1111 1111 1111").is_valid());
+        assert!(!card("4111 1111 1111 1112").is_valid());
+        assert!(money("0").is_valid());
+        assert!(money("12,50 USD").is_valid());
+        assert!(!money("12.5.0").is_valid());
+    }
+
+    #[test]
+    fn settings_and_filters_are_checked() {
+        assert!(date("2024-01-31").is_valid());
+        assert!(!date("31.01.2024").is_valid());
+        assert!(bin("220070").is_valid());
+        assert!(!bin("2200").is_valid());
+        assert!(currency("usd").is_valid());
+        assert!(!currency("XYZ").is_valid());
+        assert!(rate("92,5").is_valid());
+        assert!(!rate("0").is_valid());
+    }
+
+    #[test]
+    fn amount_must_be_positive() {
+        assert_eq!(amount("0.00"), Check::Invalid("Сумма должна быть больше нуля"));
+        assert!(amount("0.01").is_valid());
+        assert!(!amount("abc").is_valid());
+    }
+}